@@ -0,0 +1,203 @@
+use crate::runner::PartResult;
+use crate::solution::{Answer, Part};
+use anyhow::{bail, Context, Error};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+
+pub const DEFAULT_PATH: &str = "answers.toml";
+
+/// Known-good answers, read from a TOML file with one table per day:
+///
+/// ```toml
+/// [day1]
+/// part1 = 1010884
+/// part2 = 253928438
+///
+/// [day21]
+/// part2 = "vmhqr,qxfzc"
+/// ```
+#[derive(Debug, Default)]
+pub struct Answers {
+    answers: HashMap<(u32, Part), String>,
+}
+
+impl Answers {
+    pub fn load(path: &str) -> Result<Answers, Error> {
+        let s = fs::read_to_string(path).with_context(|| format!("Could not read {}", path))?;
+        Answers::parse(&s).with_context(|| format!("Could not parse {}", path))
+    }
+
+    pub fn parse(s: &str) -> Result<Answers, Error> {
+        let mut answers = HashMap::new();
+        let mut day = None;
+
+        for (line_no, line) in s.lines().enumerate() {
+            let line_no = line_no + 1;
+            let line = strip_comment(line).trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(table) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                day = match table.trim().strip_prefix("day").map(str::parse) {
+                    Some(Ok(day)) => Some(day),
+                    _ => bail!("Line {}: expected a table named [dayN]", line_no),
+                };
+                continue;
+            }
+
+            let (part, value) = parse_answer(line).with_context(|| format!("Line {}", line_no))?;
+
+            let day = match day {
+                Some(day) => day,
+                None => bail!("Line {}: answer is not inside a [dayN] table", line_no),
+            };
+
+            answers.insert((day, part), value);
+        }
+
+        Ok(Answers { answers })
+    }
+
+    pub fn get(&self, day: u32, part: Part) -> Option<&str> {
+        self.answers.get(&(day, part)).map(|s| s.as_str())
+    }
+
+    pub fn check(&self, result: &PartResult) -> Status {
+        let expected = match self.get(result.day, result.part) {
+            Some(expected) => expected,
+            None => return Status::Unchecked,
+        };
+
+        if result.answer == Answer::Unsolved {
+            Status::Fail
+        } else if result.answer.to_string() == expected {
+            Status::Pass
+        } else {
+            Status::Regressed(expected.to_string())
+        }
+    }
+}
+
+/// Remove a `#` comment, unless the `#` is inside a quoted value.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Parse a line such as `part1 = 514579` or `part2 = "a,b"`.
+pub fn parse_answer(line: &str) -> Result<(Part, String), Error> {
+    let (key, value) = match line.split_once('=') {
+        Some(kv) => kv,
+        None => bail!("expected `part1 = ...` or `part2 = ...`"),
+    };
+
+    let part = match key.trim() {
+        "part1" => Part::One,
+        "part2" => Part::Two,
+        key => bail!("unknown key {}", key),
+    };
+
+    let value = value.trim();
+    let value = if let Some(s) = value.strip_prefix('"') {
+        match s.strip_suffix('"') {
+            Some(s) => s.to_string(),
+            None => bail!("unterminated string"),
+        }
+    } else if value.parse::<i64>().is_ok() || value.parse::<u64>().is_ok() {
+        value.to_string()
+    } else {
+        bail!("expected an integer or a string");
+    };
+
+    Ok((part, value))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    /// The answer matches the recorded one.
+    Pass,
+    /// No answer could be computed.
+    Fail,
+    /// The answer differs from the recorded one.
+    Regressed(String),
+    /// There is no recorded answer to compare with.
+    Unchecked,
+}
+
+impl Status {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Status::Pass | Status::Unchecked)
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail => write!(f, "FAIL"),
+            Status::Regressed(expected) => write!(f, "REGRESSED (expected {})", expected),
+            Status::Unchecked => write!(f, "unchecked"),
+        }
+    }
+}
+
+#[test]
+fn parse_answers() {
+    let answers = Answers::parse(
+        "# Comment\n[day1]\npart1 = 514579\npart2 = -12 # trailing\n\n[day21]\npart2 = \"a,b\"\n",
+    )
+    .unwrap();
+
+    assert_eq!(answers.get(1, Part::One), Some("514579"));
+    assert_eq!(answers.get(1, Part::Two), Some("-12"));
+    assert_eq!(answers.get(21, Part::One), None);
+    assert_eq!(answers.get(21, Part::Two), Some("a,b"));
+
+    let answers = Answers::parse("[day4]\npart2 = \"a#b\" # comment\n").unwrap();
+    assert_eq!(answers.get(4, Part::Two), Some("a#b"));
+
+    assert!(Answers::parse("part1 = 1").is_err());
+    assert!(Answers::parse("[day1]\npart3 = 1").is_err());
+    assert!(Answers::parse("[day1]\npart1 = abc").is_err());
+    assert!(Answers::parse("[dayx]").is_err());
+}
+
+#[test]
+fn check_statuses() {
+    use std::time::Duration;
+
+    let answers = Answers::parse("[day1]\npart1 = 5\npart2 = 6\n").unwrap();
+    let result = |part, answer| PartResult {
+        day: 1,
+        part,
+        answer,
+        stats: Vec::new(),
+        time: Duration::ZERO,
+    };
+
+    assert_eq!(answers.check(&result(Part::One, 5u32.into())), Status::Pass);
+    assert_eq!(
+        answers.check(&result(Part::Two, 7u32.into())),
+        Status::Regressed("6".to_string())
+    );
+    assert_eq!(
+        answers.check(&result(Part::Two, Answer::Unsolved)),
+        Status::Fail
+    );
+
+    let unrecorded = PartResult {
+        day: 2,
+        ..result(Part::One, 1u32.into())
+    };
+    assert_eq!(answers.check(&unrecorded), Status::Unchecked);
+}
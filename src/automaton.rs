@@ -0,0 +1,849 @@
+use crate::grid::Grid;
+use crate::hex::{Direction, Hex};
+use crate::point::PointN;
+use anyhow::{bail, Error};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::{BuildHasherDefault, Hash, Hasher};
+use std::str::FromStr;
+
+/// A quick hasher for cells, which are small tuples of integers. This is the
+/// multiply-and-rotate hash from rustc, which is much faster than the default
+/// but does nothing to resist collisions made on purpose.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct CellHasher(u64);
+
+impl CellHasher {
+    fn add(&mut self, n: u64) {
+        self.0 = (self.0.rotate_left(5) ^ n).wrapping_mul(0x517c_c1b7_2722_0a95);
+    }
+}
+
+impl Hasher for CellHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+
+        for chunk in &mut chunks {
+            self.add(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+
+        for &b in chunks.remainder() {
+            self.add(b as u64);
+        }
+    }
+
+    fn write_u8(&mut self, n: u8) {
+        self.add(n as u64);
+    }
+
+    fn write_u32(&mut self, n: u32) {
+        self.add(n as u64);
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.add(n);
+    }
+
+    fn write_usize(&mut self, n: usize) {
+        self.add(n as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+pub type CellSet<C> = HashSet<C, BuildHasherDefault<CellHasher>>;
+pub type CellMap<C, V> = HashMap<C, V, BuildHasherDefault<CellHasher>>;
+
+/// A birth/survival rule: a dead cell comes alive if its number of live
+/// neighbours is in `birth`, and a live cell stays alive if it is in
+/// `survival`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Rule {
+    birth: u128,
+    survival: u128,
+}
+
+impl Rule {
+    pub fn new(birth: &[usize], survival: &[usize]) -> Rule {
+        let mask = |counts: &[usize]| counts.iter().fold(0u128, |m, &n| m | 1 << n);
+
+        Rule {
+            birth: mask(birth),
+            survival: mask(survival),
+        }
+    }
+
+    pub fn births(&self, neighbours: usize) -> bool {
+        neighbours < 128 && self.birth >> neighbours & 1 == 1
+    }
+
+    pub fn survives(&self, neighbours: usize) -> bool {
+        neighbours < 128 && self.survival >> neighbours & 1 == 1
+    }
+
+    pub fn next(&self, alive: bool, neighbours: usize) -> bool {
+        if alive {
+            self.survives(neighbours)
+        } else {
+            self.births(neighbours)
+        }
+    }
+}
+
+/// Rules are written like `B3/S23`, listing the single-digit neighbour counts
+/// for birth and survival.
+impl FromStr for Rule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Rule, Error> {
+        let counts = |part: &str, prefix: char| -> Result<Vec<usize>, Error> {
+            match part.strip_prefix(prefix) {
+                Some(digits) => digits
+                    .chars()
+                    .map(|c| match c.to_digit(10) {
+                        Some(d) => Ok(d as usize),
+                        None => bail!("Unexpected '{}' in rule {}", c, s),
+                    })
+                    .collect(),
+                None => bail!("Expected a rule like B3/S23, found {}", s),
+            }
+        };
+
+        match s.split_once('/') {
+            Some((b, s)) => Ok(Rule::new(&counts(b, 'B')?, &counts(s, 'S')?)),
+            None => bail!("Expected a rule like B3/S23, found {}", s),
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = |mask: u128| -> String {
+            (0..10)
+                .filter(|n| mask >> n & 1 == 1)
+                .map(|n| char::from_digit(n, 10).unwrap())
+                .collect()
+        };
+
+        write!(f, "B{}/S{}", digits(self.birth), digits(self.survival))
+    }
+}
+
+/// The shape of the space that an automaton runs in: which cells there are
+/// and which cells are each other's neighbours.
+pub trait Topology {
+    type Cell: Copy + Eq + Hash;
+
+    /// Where an automaton in this topology keeps its state.
+    type Store: Store<Self::Cell>;
+
+    /// An empty store, with no live cells and an empty frontier.
+    fn store(&self) -> Self::Store;
+
+    /// Call `f` with each neighbour of `cell`, which are the cells that count
+    /// `cell` when it is alive.
+    fn neighbours(&self, cell: Self::Cell, f: impl FnMut(Self::Cell));
+
+    /// Every cell, if there are finitely many.
+    fn cells(&self) -> Option<Vec<Self::Cell>> {
+        None
+    }
+
+    /// How many cells of the whole space this cell stands for, in topologies
+    /// that only store part of a symmetric space.
+    fn multiplicity(&self, _cell: Self::Cell) -> usize {
+        1
+    }
+}
+
+/// Which cells of a bounded grid are neighbours.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// The cells one step away in each direction.
+    Adjacent(Vec<(isize, isize)>),
+    /// The first cell seen in each direction, skipping over gaps.
+    LineOfSight(Vec<(isize, isize)>),
+}
+
+/// A finite rectangular grid, where some squares may be gaps that are never
+/// cells.
+#[derive(Debug, Clone)]
+pub struct BoundedGrid {
+    pub cells: Grid<bool>,
+    pub neighbourhood: Neighbourhood,
+}
+
+impl Topology for BoundedGrid {
+    type Cell = (usize, usize);
+    type Store = Sparse<(usize, usize)>;
+
+    fn store(&self) -> Sparse<(usize, usize)> {
+        Sparse::default()
+    }
+
+    fn neighbours(&self, (x, y): (usize, usize), mut f: impl FnMut((usize, usize))) {
+        match &self.neighbourhood {
+            Neighbourhood::Adjacent(directions) => {
+                for p in self.cells.neighbours(x, y, directions) {
+                    if self.cells[p] {
+                        f(p);
+                    }
+                }
+            }
+            Neighbourhood::LineOfSight(directions) => {
+                for &(dx, dy) in directions {
+                    if let Some(p) = self.cells.ray(x, y, dx, dy).find(|&p| self.cells[p]) {
+                        f(p);
+                    }
+                }
+            }
+        }
+    }
+
+    fn cells(&self) -> Option<Vec<(usize, usize)>> {
+        Some(
+            self.cells
+                .iter()
+                .filter(|&(_, &c)| c)
+                .map(|(p, _)| p)
+                .collect(),
+        )
+    }
+}
+
+/// A finite topology where the neighbours of each cell are worked out up
+/// front, for when they are expensive to find. Cells are numbered, and the
+/// graph remembers which cell of the original topology each number is.
+#[derive(Debug, Clone)]
+pub struct Graph<C> {
+    cells: Vec<C>,
+    index: CellMap<C, usize>,
+    /// The neighbours of cell `i` are `neighbours[starts[i]..starts[i + 1]]`.
+    starts: Vec<usize>,
+    neighbours: Vec<u32>,
+}
+
+impl<C: Copy + Eq + Hash> Graph<C> {
+    pub fn from_topology<T: Topology<Cell = C>>(topology: &T) -> Graph<C> {
+        let cells = topology
+            .cells()
+            .expect("Only finite topologies can be made into graphs");
+        assert!(cells.len() <= u32::MAX as usize, "Too many cells to number");
+        let index: CellMap<C, usize> = cells.iter().enumerate().map(|(i, &c)| (c, i)).collect();
+
+        let mut starts = vec![0];
+        let mut neighbours = Vec::new();
+
+        for &c in &cells {
+            topology.neighbours(c, |n| neighbours.push(index[&n] as u32));
+            starts.push(neighbours.len());
+        }
+
+        Graph {
+            cells,
+            index,
+            starts,
+            neighbours,
+        }
+    }
+
+    /// The cell of the original topology that `i` stands for.
+    pub fn cell(&self, i: usize) -> C {
+        self.cells[i]
+    }
+
+    pub fn index_of(&self, cell: C) -> Option<usize> {
+        self.index.get(&cell).copied()
+    }
+}
+
+impl<C: Copy + Eq + Hash> Topology for Graph<C> {
+    type Cell = usize;
+    type Store = Dense;
+
+    fn store(&self) -> Dense {
+        Dense::new(self.cells.len())
+    }
+
+    fn neighbours(&self, cell: usize, mut f: impl FnMut(usize)) {
+        for &n in &self.neighbours[self.starts[cell]..self.starts[cell + 1]] {
+            f(n as usize);
+        }
+    }
+
+    fn cells(&self) -> Option<Vec<usize>> {
+        Some((0..self.cells.len()).collect())
+    }
+}
+
+/// An unbounded D-dimensional square lattice.
+#[derive(Debug, Clone)]
+pub struct Lattice<const D: usize> {
+    offsets: Vec<PointN<D>>,
+}
+
+impl<const D: usize> Lattice<D> {
+    /// Neighbours are the 3^D - 1 cells that differ by at most one in every
+    /// coordinate.
+    pub fn moore() -> Lattice<D> {
+        Lattice {
+            offsets: PointN::moore_offsets(),
+        }
+    }
+
+    /// Neighbours are the 2 * D cells that differ by one in a single
+    /// coordinate.
+    pub fn von_neumann() -> Lattice<D> {
+        Lattice {
+            offsets: PointN::von_neumann_offsets(),
+        }
+    }
+}
+
+impl<const D: usize> Topology for Lattice<D> {
+    type Cell = PointN<D>;
+    type Store = Sparse<PointN<D>>;
+
+    fn store(&self) -> Sparse<PointN<D>> {
+        Sparse::default()
+    }
+
+    fn neighbours(&self, cell: PointN<D>, mut f: impl FnMut(PointN<D>)) {
+        for &o in &self.offsets {
+            f(cell + o);
+        }
+    }
+}
+
+/// An unbounded D-dimensional square lattice with Moore neighbourhoods, for
+/// automata that stay symmetric when the sign of any coordinate from `mirrored`
+/// onwards is flipped. Only the orthant where those coordinates are not
+/// negative is stored, and each cell there stands for all of its mirror images.
+#[derive(Debug, Clone)]
+pub struct MirroredLattice<const D: usize> {
+    mirrored: usize,
+    offsets: Vec<PointN<D>>,
+}
+
+impl<const D: usize> MirroredLattice<D> {
+    pub fn new(mirrored: usize) -> MirroredLattice<D> {
+        assert!(
+            D - mirrored < u32::BITS as usize,
+            "Too many mirrored coordinates"
+        );
+
+        MirroredLattice {
+            mirrored,
+            offsets: PointN::moore_offsets(),
+        }
+    }
+
+    /// The cell in the stored orthant that stands for `cell`.
+    pub fn canonical(&self, mut cell: PointN<D>) -> PointN<D> {
+        for i in self.mirrored..D {
+            cell[i] = cell[i].abs();
+        }
+        cell
+    }
+}
+
+impl<const D: usize> Topology for MirroredLattice<D> {
+    type Cell = PointN<D>;
+    type Store = Sparse<PointN<D>>;
+
+    fn store(&self) -> Sparse<PointN<D>> {
+        Sparse::default()
+    }
+
+    /// The neighbours in the orthant, once for each mirror image of `cell`
+    /// that they neighbour. Only images that are reflected in a plane next to
+    /// `cell` can neighbour cells in the orthant.
+    fn neighbours(&self, cell: PointN<D>, mut f: impl FnMut(PointN<D>)) {
+        let ones = (self.mirrored..D)
+            .filter(|&i| cell[i] == 1)
+            .fold(0u32, |m, i| m | 1 << (i - self.mirrored));
+
+        // Every subset of the coordinates that are 1
+        let mut flips = ones;
+
+        loop {
+            let mut image = cell;
+            for i in self.mirrored..D {
+                if flips >> (i - self.mirrored) & 1 == 1 {
+                    image[i] = -1;
+                }
+            }
+
+            for &o in &self.offsets {
+                let n = image + o;
+                if (self.mirrored..D).all(|i| n[i] >= 0) {
+                    f(n);
+                }
+            }
+
+            if flips == 0 {
+                break;
+            }
+            flips = (flips - 1) & ones;
+        }
+    }
+
+    fn multiplicity(&self, cell: PointN<D>) -> usize {
+        1 << (self.mirrored..D).filter(|&i| cell[i] != 0).count()
+    }
+}
+
+/// An unbounded grid of hexagons, where each cell has six neighbours.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct HexGrid;
+
+impl Topology for HexGrid {
+    type Cell = Hex;
+    type Store = Sparse<Hex>;
+
+    fn store(&self) -> Sparse<Hex> {
+        Sparse::default()
+    }
+
+    fn neighbours(&self, cell: Hex, mut f: impl FnMut(Hex)) {
+        for direction in Direction::ALL {
+            f(cell.neighbour(direction));
+        }
+    }
+}
+
+/// A state that was seen before, so the automaton repeats forever from
+/// `start` every `period` steps.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+}
+
+/// Where an automaton keeps its state: which cells are alive, how many live
+/// neighbours each cell has, and the frontier of cells that might change on
+/// the next step.
+pub trait Store<C>: Clone {
+    /// The live cells, in a form that can be compared to find cycles.
+    type Live: Clone + PartialEq;
+
+    fn live(&self) -> &Self::Live;
+
+    fn live_cells(&self) -> impl Iterator<Item = C> + '_;
+
+    fn is_alive(&self, cell: C) -> bool;
+
+    fn set_alive(&mut self, cell: C, alive: bool);
+
+    /// The number of live neighbours of `cell`.
+    fn count(&self, cell: C) -> usize;
+
+    fn add_neighbour(&mut self, cell: C);
+
+    fn remove_neighbour(&mut self, cell: C);
+
+    /// Add `cell` to the frontier, if it is not there already.
+    fn touch(&mut self, cell: C);
+
+    /// Empty the frontier, and return the cells that were in it.
+    fn take_frontier(&mut self) -> Vec<C>;
+}
+
+/// State for unbounded topologies, which only keeps the cells that are alive
+/// or have live neighbours.
+#[derive(Debug, Clone)]
+pub struct Sparse<C> {
+    live: CellSet<C>,
+    /// The number of live neighbours of every cell that has any.
+    counts: CellMap<C, usize>,
+    frontier: CellSet<C>,
+}
+
+impl<C> Default for Sparse<C> {
+    fn default() -> Sparse<C> {
+        Sparse {
+            live: CellSet::default(),
+            counts: CellMap::default(),
+            frontier: CellSet::default(),
+        }
+    }
+}
+
+impl<C: Copy + Eq + Hash> Store<C> for Sparse<C> {
+    type Live = CellSet<C>;
+
+    fn live(&self) -> &CellSet<C> {
+        &self.live
+    }
+
+    fn live_cells(&self) -> impl Iterator<Item = C> + '_ {
+        self.live.iter().copied()
+    }
+
+    fn is_alive(&self, cell: C) -> bool {
+        self.live.contains(&cell)
+    }
+
+    fn set_alive(&mut self, cell: C, alive: bool) {
+        if alive {
+            self.live.insert(cell);
+        } else {
+            self.live.remove(&cell);
+        }
+    }
+
+    fn count(&self, cell: C) -> usize {
+        self.counts.get(&cell).copied().unwrap_or(0)
+    }
+
+    fn add_neighbour(&mut self, cell: C) {
+        *self.counts.entry(cell).or_insert(0) += 1;
+    }
+
+    fn remove_neighbour(&mut self, cell: C) {
+        if let Some(count) = self.counts.get_mut(&cell) {
+            *count -= 1;
+            if *count == 0 {
+                self.counts.remove(&cell);
+            }
+        }
+    }
+
+    fn touch(&mut self, cell: C) {
+        self.frontier.insert(cell);
+    }
+
+    fn take_frontier(&mut self) -> Vec<C> {
+        self.frontier.drain().collect()
+    }
+}
+
+/// State for finite topologies whose cells are numbered from 0, kept in
+/// vectors indexed by cell.
+#[derive(Debug, Clone)]
+pub struct Dense {
+    live: Vec<bool>,
+    counts: Vec<u8>,
+    /// The frontier is `frontier[..frontier_len]`. There is room for one more
+    /// cell than there are, so that `touch` can always write the cell and then
+    /// only count it if it is new.
+    frontier: Vec<usize>,
+    frontier_len: usize,
+    /// Whether each cell is in the frontier.
+    seen: Vec<bool>,
+}
+
+impl Dense {
+    pub fn new(len: usize) -> Dense {
+        Dense {
+            live: vec![false; len],
+            counts: vec![0; len],
+            frontier: vec![0; len + 1],
+            frontier_len: 0,
+            seen: vec![false; len],
+        }
+    }
+}
+
+impl Store<usize> for Dense {
+    type Live = Vec<bool>;
+
+    fn live(&self) -> &Vec<bool> {
+        &self.live
+    }
+
+    fn live_cells(&self) -> impl Iterator<Item = usize> + '_ {
+        self.live
+            .iter()
+            .enumerate()
+            .filter(|&(_, &alive)| alive)
+            .map(|(i, _)| i)
+    }
+
+    fn is_alive(&self, cell: usize) -> bool {
+        self.live[cell]
+    }
+
+    fn set_alive(&mut self, cell: usize, alive: bool) {
+        self.live[cell] = alive;
+    }
+
+    fn count(&self, cell: usize) -> usize {
+        self.counts[cell] as usize
+    }
+
+    fn add_neighbour(&mut self, cell: usize) {
+        self.counts[cell] = self.counts[cell]
+            .checked_add(1)
+            .expect("Cells can have at most 255 live neighbours");
+    }
+
+    fn remove_neighbour(&mut self, cell: usize) {
+        self.counts[cell] -= 1;
+    }
+
+    fn touch(&mut self, cell: usize) {
+        let seen = std::mem::replace(&mut self.seen[cell], true);
+        self.frontier[self.frontier_len] = cell;
+        self.frontier_len += !seen as usize;
+    }
+
+    fn take_frontier(&mut self) -> Vec<usize> {
+        let len = std::mem::take(&mut self.frontier_len);
+
+        // When much of the graph is in the frontier, going through it in order
+        // of cell is kinder to the cache than the order it was touched in
+        if len > self.seen.len() / 8 {
+            let mut frontier = Vec::with_capacity(len);
+
+            for (cell, seen) in self.seen.iter_mut().enumerate() {
+                if *seen {
+                    frontier.push(cell);
+                    *seen = false;
+                }
+            }
+
+            frontier
+        } else {
+            let frontier = self.frontier[..len].to_vec();
+
+            for &cell in &frontier {
+                self.seen[cell] = false;
+            }
+
+            frontier
+        }
+    }
+}
+
+/// A cellular automaton, as the set of live cells in some topology.
+#[derive(Debug, Clone)]
+pub struct Automaton<T: Topology> {
+    topology: T,
+    rule: Rule,
+    store: T::Store,
+    generation: usize,
+}
+
+impl<T: Topology> Automaton<T> {
+    pub fn new(topology: T, rule: Rule, live: impl IntoIterator<Item = T::Cell>) -> Automaton<T> {
+        let mut store = topology.store();
+
+        for cell in live {
+            if !store.is_alive(cell) {
+                store.set_alive(cell, true);
+                topology.neighbours(cell, |n| store.add_neighbour(n));
+            }
+        }
+
+        // At first any cell might change, but cells with no live neighbours
+        // can only change if they are born from nothing
+        if rule.births(0) {
+            match topology.cells() {
+                Some(cells) => cells.into_iter().for_each(|c| store.touch(c)),
+                None => panic!(
+                    "{} needs a finite topology, because every isolated cell is born",
+                    rule
+                ),
+            }
+        } else {
+            let live: Vec<T::Cell> = store.live_cells().collect();
+
+            for cell in live {
+                store.touch(cell);
+                topology.neighbours(cell, |n| store.touch(n));
+            }
+        }
+
+        Automaton {
+            topology,
+            rule,
+            store,
+            generation: 0,
+        }
+    }
+
+    pub fn topology(&self) -> &T {
+        &self.topology
+    }
+
+    pub fn live(&self) -> &<T::Store as Store<T::Cell>>::Live {
+        self.store.live()
+    }
+
+    pub fn live_cells(&self) -> impl Iterator<Item = T::Cell> + '_ {
+        self.store.live_cells()
+    }
+
+    pub fn is_alive(&self, cell: T::Cell) -> bool {
+        self.store.is_alive(cell)
+    }
+
+    /// The number of live cells, counting every cell that a stored cell
+    /// stands for.
+    pub fn population(&self) -> usize {
+        self.live_cells()
+            .map(|c| self.topology.multiplicity(c))
+            .sum()
+    }
+
+    /// The number of steps taken so far.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Advance by one step, and return whether anything changed.
+    pub fn step(&mut self) -> bool {
+        let changes: Vec<T::Cell> = self
+            .store
+            .take_frontier()
+            .into_iter()
+            .filter(|&cell| {
+                let alive = self.store.is_alive(cell);
+                self.rule.next(alive, self.store.count(cell)) != alive
+            })
+            .collect();
+
+        for &cell in &changes {
+            let store = &mut self.store;
+            let born = !store.is_alive(cell);
+            store.set_alive(cell, born);
+            store.touch(cell);
+
+            self.topology.neighbours(cell, |n| {
+                if born {
+                    store.add_neighbour(n);
+                } else {
+                    store.remove_neighbour(n);
+                }
+
+                store.touch(n);
+            });
+        }
+
+        self.generation += 1;
+
+        !changes.is_empty()
+    }
+
+    pub fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    /// Step until nothing changes, and return the number of steps that
+    /// changed something, or `None` if more than `limit` steps change
+    /// something. The step that finds nothing changed is not counted.
+    pub fn run_to_fixed_point(&mut self, limit: usize) -> Option<usize> {
+        (0..=limit).find(|_| !self.step())
+    }
+
+    /// Step until a state repeats, for at most `limit` steps. The cycle's
+    /// start is counted in generations from the current state.
+    pub fn find_cycle(&mut self, limit: usize) -> Option<Cycle> {
+        let mut states = vec![self.live().clone()];
+        let mut seen: HashMap<u64, Vec<usize>> = HashMap::new();
+        seen.entry(self.fingerprint()).or_default().push(0);
+
+        for steps in 1..=limit {
+            self.step();
+
+            let candidates = seen.entry(self.fingerprint()).or_default();
+
+            if let Some(&start) = candidates.iter().find(|&&i| states[i] == *self.live()) {
+                return Some(Cycle {
+                    start,
+                    period: steps - start,
+                });
+            }
+
+            candidates.push(steps);
+            states.push(self.live().clone());
+        }
+
+        None
+    }
+
+    /// A hash of the live cells that does not depend on their order.
+    fn fingerprint(&self) -> u64 {
+        self.live_cells()
+            .map(|c| {
+                let mut hasher = DefaultHasher::new();
+                c.hash(&mut hasher);
+                hasher.finish()
+            })
+            .fold(0, u64::wrapping_add)
+    }
+}
+
+#[test]
+fn automata() {
+    assert_eq!("B3/S23".parse::<Rule>().unwrap(), Rule::new(&[3], &[2, 3]));
+    assert_eq!(Rule::new(&[0], &[0, 1, 2, 3]).to_string(), "B0/S0123");
+    assert!("B3S23".parse::<Rule>().is_err());
+    assert!("B3/Sx".parse::<Rule>().is_err());
+
+    assert_eq!(Lattice::<3>::moore().offsets.len(), 26);
+    assert_eq!(Lattice::<4>::von_neumann().offsets.len(), 8);
+
+    // A blinker has period two, and a block never changes
+    let life = "B3/S23".parse().unwrap();
+    let mut blinker = Automaton::new(
+        Lattice::<2>::moore(),
+        life,
+        [[0, -1], [0, 0], [0, 1]].map(PointN),
+    );
+    assert_eq!(
+        blinker.find_cycle(10),
+        Some(Cycle {
+            start: 0,
+            period: 2
+        })
+    );
+
+    let mut block = Automaton::new(
+        Lattice::<2>::moore(),
+        life,
+        [[0, 0], [0, 1], [1, 0], [1, 1]].map(PointN),
+    );
+    assert_eq!(block.run_to_fixed_point(0), Some(0));
+
+    let mut glider = Automaton::new(
+        Lattice::<2>::moore(),
+        life,
+        [[1, 0], [2, 1], [0, 2], [1, 2], [2, 2]].map(PointN),
+    );
+    assert_eq!(glider.run_to_fixed_point(10), None);
+    assert_eq!(glider.population(), 5);
+
+    // The same blinker in a bounded grid, where cells are kept in vectors
+    let grid = Graph::from_topology(&BoundedGrid {
+        cells: crate::grid::parse_bool("#####\n#####\n#####").unwrap(),
+        neighbourhood: Neighbourhood::Adjacent(crate::grid::DIRECTIONS_8.to_vec()),
+    });
+    let live = [(1, 1), (2, 1), (3, 1)].map(|p| grid.index_of(p).unwrap());
+    let mut blinker = Automaton::new(grid, life, live);
+    assert_eq!(
+        blinker.find_cycle(10),
+        Some(Cycle {
+            start: 0,
+            period: 2
+        })
+    );
+    assert_eq!(blinker.population(), 3);
+
+    // Seats see past the gap between them, but not past another seat
+    let seats = Graph::from_topology(&BoundedGrid {
+        cells: crate::grid::parse_bool("#.##").unwrap(),
+        neighbourhood: Neighbourhood::LineOfSight(vec![(-1, 0), (1, 0)]),
+    });
+    let mut seen = Vec::new();
+    seats.neighbours(seats.index_of((2, 0)).unwrap(), |n| {
+        seen.push(seats.cell(n))
+    });
+    assert_eq!(seen, [(0, 0), (3, 0)]);
+}
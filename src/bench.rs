@@ -0,0 +1,339 @@
+use crate::parse::ParseError;
+use crate::runner::{self, format_duration, PartError};
+use crate::solution::{DynSolution, Part, Settings};
+use anyhow::{anyhow, Context, Error};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_ITERATIONS: usize = 5;
+
+/// How much slower than the baseline a stage can get before it counts as a
+/// regression, as a fraction.
+pub const DEFAULT_THRESHOLD: f64 = 0.1;
+
+/// A part of a solution that is timed separately.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Stage {
+    Parse,
+    Part(Part),
+}
+
+impl Stage {
+    fn name(self) -> &'static str {
+        match self {
+            Stage::Parse => "parse",
+            Stage::Part(Part::One) => "part1",
+            Stage::Part(Part::Two) => "part2",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Stage> {
+        match name {
+            "parse" => Some(Stage::Parse),
+            "part1" => Some(Stage::Part(Part::One)),
+            "part2" => Some(Stage::Part(Part::Two)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &mut [Duration]) -> Stats {
+        assert!(!samples.is_empty(), "No samples");
+        samples.sort_unstable();
+
+        let n = samples.len();
+        let median = if n % 2 == 1 {
+            samples[n / 2]
+        } else {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        };
+
+        Stats {
+            min: samples[0],
+            median,
+            max: samples[n - 1],
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct BenchResult {
+    pub day: u32,
+    pub stage: Stage,
+    pub stats: Stats,
+}
+
+/// Time parsing and the selected parts of one day, `iterations` times each.
+/// A part that fails is not timed, and its error is returned instead.
+pub fn bench_day(
+    day: u32,
+    solution: &dyn DynSolution,
+    input: &str,
+    parts: &[Part],
+    iterations: usize,
+    settings: &Settings,
+) -> Result<Vec<Result<BenchResult, PartError>>, ParseError> {
+    let mut parse_samples = Vec::new();
+    let mut part_samples: Vec<Result<Vec<Duration>, PartError>> =
+        parts.iter().map(|_| Ok(Vec::new())).collect();
+
+    for _ in 0..iterations.max(1) {
+        let start = Instant::now();
+        let parsed = solution.parse(black_box(input))?;
+        parse_samples.push(start.elapsed());
+
+        for (samples, &part) in part_samples.iter_mut().zip(parts) {
+            if let Ok(times) = samples {
+                let start = Instant::now();
+                let output = runner::run_part(day, solution, &parsed, part, settings);
+                let time = start.elapsed();
+
+                match black_box(output) {
+                    Ok(_) => times.push(time),
+                    Err(e) => *samples = Err(e),
+                }
+            }
+        }
+    }
+
+    let result = |stage, mut samples: Vec<Duration>| BenchResult {
+        day,
+        stage,
+        stats: Stats::from_samples(&mut samples),
+    };
+
+    let mut results = vec![Ok(result(Stage::Parse, parse_samples))];
+    results.extend(
+        parts
+            .iter()
+            .zip(part_samples)
+            .map(|(&part, samples)| samples.map(|samples| result(Stage::Part(part), samples))),
+    );
+
+    Ok(results)
+}
+
+/// Median times from an earlier run, to compare against.
+///
+/// Baselines are stored as JSON:
+///
+/// ```json
+/// {"results": [
+/// {"day":1,"stage":"parse","min_ns":10400,"median_ns":10950,"max_ns":15200}
+/// ]}
+/// ```
+#[derive(Debug, Default)]
+pub struct Baseline {
+    medians: HashMap<(u32, Stage), Duration>,
+}
+
+impl Baseline {
+    pub fn load(path: &str) -> Result<Baseline, Error> {
+        let s = fs::read_to_string(path).with_context(|| format!("Could not read {}", path))?;
+        Baseline::parse(&s).with_context(|| format!("Could not parse {}", path))
+    }
+
+    pub fn parse(s: &str) -> Result<Baseline, Error> {
+        let value: Value = serde_json::from_str(s)?;
+        let results = value
+            .get("results")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow!("Expected a \"results\" array"))?;
+
+        let mut medians = HashMap::new();
+
+        for (i, r) in results.iter().enumerate() {
+            let invalid = || anyhow!("Result {} is invalid", i + 1);
+
+            let day = r.get("day").and_then(Value::as_u64).ok_or_else(invalid)?;
+            let stage = r
+                .get("stage")
+                .and_then(Value::as_str)
+                .and_then(Stage::from_name)
+                .ok_or_else(invalid)?;
+            let median = r
+                .get("median_ns")
+                .and_then(Value::as_u64)
+                .ok_or_else(invalid)?;
+
+            medians.insert((day as u32, stage), Duration::from_nanos(median));
+        }
+
+        Ok(Baseline { medians })
+    }
+
+    pub fn save(path: &str, results: &[BenchResult]) -> Result<(), Error> {
+        let lines: Vec<String> = results
+            .iter()
+            .map(|r| {
+                json!({
+                    "day": r.day,
+                    "stage": r.stage.name(),
+                    "min_ns": r.stats.min.as_nanos() as u64,
+                    "median_ns": r.stats.median.as_nanos() as u64,
+                    "max_ns": r.stats.max.as_nanos() as u64,
+                })
+                .to_string()
+            })
+            .collect();
+
+        let s = format!("{{\"results\": [\n{}\n]}}\n", lines.join(",\n"));
+        fs::write(path, s).with_context(|| format!("Could not write {}", path))
+    }
+
+    /// How the median time of a result compares with the baseline, as a
+    /// fraction: 0.25 is 25% slower.
+    pub fn change(&self, result: &BenchResult) -> Option<f64> {
+        let old = self.medians.get(&(result.day, result.stage))?;
+
+        if old.is_zero() {
+            return None;
+        }
+
+        Some(result.stats.median.as_secs_f64() / old.as_secs_f64() - 1.0)
+    }
+}
+
+/// Print a table of results. If a baseline is given, each result is compared
+/// with it and the number of regressions is returned.
+pub fn print_table(results: &[BenchResult], baseline: Option<&Baseline>, threshold: f64) -> usize {
+    print!("Day  Stage  {:>10}  {:>10}  {:>10}", "Min", "Median", "Max");
+    if baseline.is_some() {
+        print!("  {:>8}  Status", "Change");
+    }
+    println!();
+
+    let mut regressions = 0;
+
+    for r in results {
+        print!(
+            "{:>3}  {:<5}  {:>10}  {:>10}  {:>10}",
+            r.day,
+            r.stage,
+            format_duration(r.stats.min),
+            format_duration(r.stats.median),
+            format_duration(r.stats.max)
+        );
+
+        if let Some(baseline) = baseline {
+            match baseline.change(r) {
+                Some(change) => {
+                    let status = if change > threshold {
+                        regressions += 1;
+                        "REGRESSED"
+                    } else {
+                        "ok"
+                    };
+
+                    print!("  {:>+7.1}%  {}", change * 100.0, status);
+                }
+                None => print!("  {:>8}  new", "-"),
+            }
+        }
+
+        println!();
+    }
+
+    regressions
+}
+
+#[test]
+fn stats_and_baselines() {
+    let ms = Duration::from_millis;
+
+    let stats = Stats::from_samples(&mut [ms(5), ms(1), ms(9), ms(3)]);
+    assert_eq!(
+        stats,
+        Stats {
+            min: ms(1),
+            median: ms(4),
+            max: ms(9)
+        }
+    );
+    assert_eq!(
+        Stats::from_samples(&mut [ms(2), ms(7), ms(1)]).median,
+        ms(2)
+    );
+
+    let result = |stage, median| BenchResult {
+        day: 15,
+        stage,
+        stats: Stats {
+            min: median,
+            median,
+            max: median,
+        },
+    };
+
+    let path = std::env::temp_dir().join(format!("aoc-bench-test-{}.json", std::process::id()));
+    let path = path.to_str().unwrap();
+    Baseline::save(
+        path,
+        &[
+            result(Stage::Parse, ms(2)),
+            result(Stage::Part(Part::Two), ms(100)),
+        ],
+    )
+    .unwrap();
+    let baseline = Baseline::load(path).unwrap();
+    fs::remove_file(path).unwrap();
+
+    assert_eq!(baseline.change(&result(Stage::Parse, ms(3))), Some(0.5));
+    assert_eq!(
+        baseline.change(&result(Stage::Part(Part::Two), ms(50))),
+        Some(-0.5)
+    );
+    assert_eq!(
+        baseline.change(&result(Stage::Part(Part::One), ms(50))),
+        None
+    );
+
+    assert!(Baseline::parse("{\"results\": [{\"day\": 1}]}").is_err());
+}
+
+#[test]
+fn bench_failing_parts() {
+    let layout = "L.LL.LL.LL\nLLLLLLL.LL\nL.L.L..L..\nLLLL.LL.LL\nL.LL.LL.LL\n\
+                  L.LLLLL.LL\n..L.L.....\nLLLLLLLLLL\nL.LLLLLL.L\nL.LLLLL.LL\n";
+    let settings = Settings {
+        max_rounds: Some(5),
+    };
+    let outcomes = bench_day(
+        11,
+        crate::registry::get(11).unwrap(),
+        layout,
+        &Part::BOTH,
+        2,
+        &settings,
+    )
+    .unwrap();
+
+    let stages: Vec<Stage> = outcomes
+        .iter()
+        .filter_map(|o| o.as_ref().ok())
+        .map(|r| r.stage)
+        .collect();
+    assert_eq!(stages, [Stage::Parse, Stage::Part(Part::One)]);
+    assert_eq!(
+        outcomes[2].as_ref().unwrap_err().to_string(),
+        "day 11, part 2: The seating has not settled after 5 rounds"
+    );
+}
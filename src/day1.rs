@@ -1,14 +1,27 @@
-pub fn solve() {
-    let input: Vec<i32> = include_str!("../input/day1")
-        .lines()
-        .map(|s| s.parse().unwrap())
-        .collect();
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution};
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u32 = 1;
+
+    type Input = Vec<i32>;
+
+    fn parse(&self, input: &str) -> Result<Vec<i32>, ParseError> {
+        parse::lines(input).map(|l| l.parse_number()).collect()
+    }
+
+    fn part1(&self, input: &Vec<i32>) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(&self, input: &Vec<i32>) -> Answer {
+        part2(input).into()
+    }
 }
 
-fn part1(input: &[i32]) -> i32 {
+pub fn part1(input: &[i32]) -> i32 {
     for (i, first) in input.iter().enumerate() {
         for second in &input[i + 1..] {
             if first + second == 2020 {
@@ -20,7 +33,7 @@ fn part1(input: &[i32]) -> i32 {
     panic!("No answer found")
 }
 
-fn part2(input: &[i32]) -> i32 {
+pub fn part2(input: &[i32]) -> i32 {
     for (i, first) in input.iter().enumerate() {
         for (j, second) in input[i + 1..].iter().enumerate() {
             for third in &input[i + j + 1..] {
@@ -1,14 +1,27 @@
-pub fn solve() {
-    let input: Vec<u64> = include_str!("../input/day10")
-        .lines()
-        .map(|s| s.parse().unwrap())
-        .collect();
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution};
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u32 = 10;
+
+    type Input = Vec<u64>;
+
+    fn parse(&self, input: &str) -> Result<Vec<u64>, ParseError> {
+        parse::lines(input).map(|l| l.parse_number()).collect()
+    }
+
+    fn part1(&self, input: &Vec<u64>) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(&self, input: &Vec<u64>) -> Answer {
+        part2(input).into()
+    }
 }
 
-fn part1(adaptors: &[u64]) -> u64 {
+pub fn part1(adaptors: &[u64]) -> u64 {
     let joltages = joltages_from_adaptors(adaptors);
 
     let mut num_diff_1 = 0;
@@ -25,7 +38,7 @@ fn part1(adaptors: &[u64]) -> u64 {
     num_diff_1 * num_diff_3
 }
 
-fn part2(adaptors: &[u64]) -> u64 {
+pub fn part2(adaptors: &[u64]) -> u64 {
     let joltages = joltages_from_adaptors(adaptors);
 
     let mut num_configs = vec![1];
@@ -46,7 +59,7 @@ fn part2(adaptors: &[u64]) -> u64 {
     num_configs[num_configs.len() - 1]
 }
 
-fn joltages_from_adaptors(adaptors: &[u64]) -> Vec<u64> {
+pub fn joltages_from_adaptors(adaptors: &[u64]) -> Vec<u64> {
     let mut joltages = adaptors.to_vec();
     joltages.push(0); // Socket
     joltages.push(joltages.iter().max().unwrap() + 3); // Laptop
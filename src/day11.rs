@@ -1,39 +1,93 @@
-pub fn solve() {
-    let grid = Grid::from_str(include_str!("../input/day11"));
+use crate::automaton::{Automaton, BoundedGrid, Graph, Neighbourhood, Rule};
+use crate::grid::{Grid, DIRECTIONS_8};
+use crate::parse::ParseError;
+use crate::solution::{Output, Part, Settings, Solution};
+use anyhow::{bail, Error};
 
-    println!("Part 1: {}", part1(&grid));
-    println!("Part 2: {}", part2(&grid));
-}
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u32 = 11;
 
-fn part1(grid: &Grid) -> u32 {
-    let mut grid = grid.clone();
+    type Input = Layout;
 
-    for _ in 0..100 {
-        grid.step_part1();
+    fn parse(&self, input: &str) -> Result<Layout, ParseError> {
+        parse_layout(input)
     }
 
-    grid.count_occupied()
+    fn run(&self, input: &Layout, part: Part, settings: &Settings) -> Result<Output, Error> {
+        let max_rounds = settings.max_rounds.unwrap_or(MAX_ROUNDS);
+        let settled = match part {
+            Part::One => settle_part1(input, max_rounds)?,
+            Part::Two => settle_part2(input, max_rounds)?,
+        };
+
+        Ok(Output {
+            answer: settled.occupied.into(),
+            stats: vec![("rounds", settled.rounds as u64)],
+        })
+    }
 }
 
-fn part2(grid: &Grid) -> u32 {
-    let mut grid = grid.clone();
+/// How many rounds the seating can take to settle before we give up, unless
+/// `--max-rounds` says otherwise.
+pub const MAX_ROUNDS: usize = 1000;
 
-    for _ in 0..100 {
-        grid.step_part2();
-    }
+pub fn settle_part1(layout: &Layout, max_rounds: usize) -> Result<Settled, Error> {
+    settle(seating_part1(layout), max_rounds)
+}
+
+pub fn settle_part2(layout: &Layout, max_rounds: usize) -> Result<Settled, Error> {
+    settle(seating_part2(layout), max_rounds)
+}
+
+/// Empty seats are taken if no neighbours are occupied, and occupied seats
+/// are left if 4 or more are.
+pub fn seating_part1(layout: &Layout) -> Seats {
+    seating(
+        layout,
+        Neighbourhood::Adjacent(DIRECTIONS_8.to_vec()),
+        Rule::new(&[0], &[0, 1, 2, 3]),
+    )
+}
+
+/// Empty seats are taken if no visible seats are occupied, and occupied seats
+/// are left if 5 or more are.
+pub fn seating_part2(layout: &Layout) -> Seats {
+    seating(
+        layout,
+        Neighbourhood::LineOfSight(DIRECTIONS_8.to_vec()),
+        Rule::new(&[0], &[0, 1, 2, 3, 4]),
+    )
+}
+
+/// The seating once people stop moving.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Settled {
+    pub occupied: usize,
+    /// The number of rounds in which someone moved.
+    pub rounds: usize,
+}
 
-    grid.count_occupied()
+pub fn settle(mut seats: Seats, max_rounds: usize) -> Result<Settled, Error> {
+    match seats.run_to_fixed_point(max_rounds) {
+        Some(rounds) => Ok(Settled {
+            occupied: seats.population(),
+            rounds,
+        }),
+        None => bail!("The seating has not settled after {} rounds", max_rounds),
+    }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq)]
-enum Square {
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Square {
     Floor,
     Empty,
     Occupied,
 }
 
 impl Square {
-    fn from_char(c: char) -> Option<Square> {
+    pub fn from_char(c: char) -> Option<Square> {
         match c {
             '.' => Some(Square::Floor),
             'L' => Some(Square::Empty),
@@ -43,164 +97,63 @@ impl Square {
     }
 }
 
-#[derive(Clone)]
-struct Grid {
-    width: usize,
-    height: usize,
-    squares: Vec<Square>,
-}
-
-impl Grid {
-    fn from_str(s: &str) -> Grid {
-        let height = s.lines().count();
-        let width = s.lines().next().unwrap().len();
-        let squares: Vec<Square> = s.chars().filter_map(Square::from_char).collect();
-
-        Grid {
-            width,
-            height,
-            squares,
-        }
-    }
-
-    fn at(&self, x: isize, y: isize) -> Square {
-        if x >= 0 && x < self.width as isize && y >= 0 && y < self.height as isize {
-            self.squares[x as usize + y as usize * self.width]
-        } else {
-            Square::Floor
-        }
-    }
-
-    fn adjacent_squares(&self, x: usize, y: usize) -> [Square; 8] {
-        let x = x as isize;
-        let y = y as isize;
-
-        [
-            self.at(x - 1, y - 1),
-            self.at(x, y - 1),
-            self.at(x + 1, y - 1),
-            self.at(x + 1, y),
-            self.at(x + 1, y + 1),
-            self.at(x, y + 1),
-            self.at(x - 1, y + 1),
-            self.at(x - 1, y),
-        ]
-    }
-
-    fn visible_seat_in_dir(&self, x: usize, y: usize, dx: isize, dy: isize) -> Square {
-        let mut x = x as isize;
-        let mut y = y as isize;
-
-        loop {
-            x += dx;
-            y += dy;
-
-            if x < 0 || x >= self.width as isize || y < 0 || y >= self.height as isize {
-                return Square::Floor;
-            }
-
-            let s = self.at(x, y);
-
-            if s != Square::Floor {
-                return s;
-            }
-        }
-    }
-
-    fn visible_seats(&self, x: usize, y: usize) -> [Square; 8] {
-        [
-            self.visible_seat_in_dir(x, y, -1, -1),
-            self.visible_seat_in_dir(x, y, 0, -1),
-            self.visible_seat_in_dir(x, y, 1, -1),
-            self.visible_seat_in_dir(x, y, 1, 0),
-            self.visible_seat_in_dir(x, y, 1, 1),
-            self.visible_seat_in_dir(x, y, 0, 1),
-            self.visible_seat_in_dir(x, y, -1, 1),
-            self.visible_seat_in_dir(x, y, -1, 0),
-        ]
-    }
-
-    fn next_square_part1(&self, x: usize, y: usize) -> Square {
-        let current_square = self.at(x as isize, y as isize);
-
-        if current_square == Square::Floor {
-            return Square::Floor;
-        }
-
-        let mut count = 0;
-
-        for &s in &self.adjacent_squares(x, y) {
-            if s == Square::Occupied {
-                count += 1;
-            }
-        }
-
-        if count == 0 {
-            Square::Occupied
-        } else if count >= 4 {
-            Square::Empty
-        } else {
-            current_square
-        }
-    }
-
-    fn next_square_part2(&self, x: usize, y: usize) -> Square {
-        let current_square = self.at(x as isize, y as isize);
+pub type Layout = Grid<Square>;
 
-        if current_square == Square::Floor {
-            return Square::Floor;
-        }
-
-        let mut count = 0;
-
-        for &s in &self.visible_seats(x, y) {
-            if s == Square::Occupied {
-                count += 1;
-            }
-        }
-
-        if count == 0 {
-            Square::Occupied
-        } else if count >= 5 {
-            Square::Empty
-        } else {
-            current_square
-        }
-    }
-
-    fn count_occupied(&self) -> u32 {
-        let mut count = 0;
-
-        for &s in &self.squares {
-            if s == Square::Occupied {
-                count += 1;
-            }
-        }
-
-        count
-    }
+pub fn parse_layout(s: &str) -> Result<Layout, ParseError> {
+    Grid::parse(s, Square::from_char)
+}
 
-    fn step_part1(&mut self) {
-        let mut new_squares = self.squares.clone();
+/// Seats are numbered, and occupied seats are alive.
+pub type Seats = Automaton<Graph<(usize, usize)>>;
+
+/// The seats as an automaton where occupied seats are alive. Floor squares
+/// are never seats, and the seats that each seat looks at are found once up
+/// front.
+pub fn seating(layout: &Layout, neighbourhood: Neighbourhood, rule: Rule) -> Seats {
+    let seats = Graph::from_topology(&BoundedGrid {
+        cells: layout.map(|&s| s != Square::Floor),
+        neighbourhood,
+    });
+    let occupied: Vec<usize> = layout
+        .iter()
+        .filter(|&(_, &s)| s == Square::Occupied)
+        .filter_map(|(p, _)| seats.index_of(p))
+        .collect();
+
+    Automaton::new(seats, rule, occupied)
+}
 
-        for x in 0..self.width {
-            for y in 0..self.height {
-                new_squares[x + y * self.width] = self.next_square_part1(x, y);
-            }
-        }
+/// Draw the seats like the puzzle does, with `#` for occupied seats.
+pub fn render(layout: &Layout, seats: &Seats) -> String {
+    let mut squares = layout.map(|&s| match s {
+        Square::Floor => Square::Floor,
+        _ => Square::Empty,
+    });
 
-        self.squares = new_squares;
+    for seat in seats.live_cells() {
+        squares[seats.topology().cell(seat)] = Square::Occupied;
     }
 
-    fn step_part2(&mut self) {
-        let mut new_squares = self.squares.clone();
-
-        for x in 0..self.width {
-            for y in 0..self.height {
-                new_squares[x + y * self.width] = self.next_square_part2(x, y);
-            }
-        }
+    squares.render(|s| match s {
+        Square::Floor => '.',
+        Square::Empty => 'L',
+        Square::Occupied => '#',
+    })
+}
 
-        self.squares = new_squares;
-    }
+#[test]
+fn settle_example() {
+    let layout = parse_layout(
+        "L.LL.LL.LL\nLLLLLLL.LL\nL.L.L..L..\nLLLL.LL.LL\nL.LL.LL.LL\n\
+         L.LLLLL.LL\n..L.L.....\nLLLLLLLLLL\nL.LLLLLL.L\nL.LLLLL.LL",
+    )
+    .unwrap();
+
+    let settled = settle_part1(&layout, 100).unwrap();
+    assert_eq!((settled.occupied, settled.rounds), (37, 5));
+    let settled = settle_part2(&layout, 100).unwrap();
+    assert_eq!((settled.occupied, settled.rounds), (26, 6));
+
+    assert_eq!(settle_part1(&layout, 5).unwrap().rounds, 5);
+    assert!(settle_part1(&layout, 4).is_err());
 }
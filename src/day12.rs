@@ -1,17 +1,28 @@
-use anyhow::{bail, Error};
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution};
 use std::str::FromStr;
 
-pub fn solve() {
-    let input: Vec<Move> = include_str!("../input/day12")
-        .lines()
-        .map(|s| s.parse().unwrap())
-        .collect();
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u32 = 12;
+
+    type Input = Vec<Move>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Move>, ParseError> {
+        parse::parse_lines(input)
+    }
+
+    fn part1(&self, input: &Vec<Move>) -> Answer {
+        part1(input).into()
+    }
 
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    fn part2(&self, input: &Vec<Move>) -> Answer {
+        part2(input).into()
+    }
 }
 
-fn part1(moves: &[Move]) -> i32 {
+pub fn part1(moves: &[Move]) -> i32 {
     let mut p = (0, 0);
     let mut wp = (1, 0);
 
@@ -24,7 +35,7 @@ fn part1(moves: &[Move]) -> i32 {
     p.0.abs() + p.1.abs()
 }
 
-fn part2(moves: &[Move]) -> i32 {
+pub fn part2(moves: &[Move]) -> i32 {
     let mut p = (0, 0);
     let mut wp = (10, 1);
 
@@ -37,7 +48,7 @@ fn part2(moves: &[Move]) -> i32 {
     p.0.abs() + p.1.abs()
 }
 
-fn move_abs(m: &Move, p: (i32, i32)) -> (i32, i32) {
+pub fn move_abs(m: &Move, p: (i32, i32)) -> (i32, i32) {
     match m {
         Move::North(amt) => (p.0, p.1 + amt),
         Move::East(amt) => (p.0 + amt, p.1),
@@ -45,7 +56,7 @@ fn move_abs(m: &Move, p: (i32, i32)) -> (i32, i32) {
     }
 }
 
-fn rotate(m: &Move, p: (i32, i32)) -> (i32, i32) {
+pub fn rotate(m: &Move, p: (i32, i32)) -> (i32, i32) {
     match m {
         Move::Left(amt) => match amt {
             90 => (-p.1, p.0),
@@ -57,7 +68,7 @@ fn rotate(m: &Move, p: (i32, i32)) -> (i32, i32) {
     }
 }
 
-fn move_rel(m: &Move, p: (i32, i32), wp: (i32, i32)) -> (i32, i32) {
+pub fn move_rel(m: &Move, p: (i32, i32), wp: (i32, i32)) -> (i32, i32) {
     match m {
         Move::Forward(amt) => (p.0 + amt * wp.0, p.1 + amt * wp.1),
         _ => p,
@@ -65,7 +76,7 @@ fn move_rel(m: &Move, p: (i32, i32), wp: (i32, i32)) -> (i32, i32) {
 }
 
 #[derive(Debug, Copy, Clone)]
-enum Move {
+pub enum Move {
     North(i32),
     East(i32),
     Left(i32),
@@ -73,16 +84,20 @@ enum Move {
 }
 
 impl FromStr for Move {
-    type Err = Error;
+    type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Move, Error> {
-        if s.is_empty() {
-            bail!("Empty move");
-        }
+    fn from_str(s: &str) -> Result<Move, ParseError> {
+        let mut chars = s.chars();
+        let dir = chars
+            .next()
+            .ok_or_else(|| ParseError::new(1, "expected a move like \"F10\""))?;
+        let amt = parse::number(s, chars.as_str())?;
 
-        let amt = s[1..].parse()?;
+        if (dir == 'L' || dir == 'R') && ![90, 180, 270].contains(&amt) {
+            return Err(ParseError::new(2, "expected an angle of 90, 180 or 270"));
+        }
 
-        Ok(match s.chars().next().unwrap() {
+        Ok(match dir {
             'N' => Move::North(amt),
             'S' => Move::North(-amt),
             'E' => Move::East(amt),
@@ -90,7 +105,7 @@ impl FromStr for Move {
             'L' => Move::Left(amt),
             'R' => Move::Left(360 - amt),
             'F' => Move::Forward(amt),
-            _ => bail!("Invalid direction"),
+            _ => return Err(ParseError::new(1, format!("unknown direction '{}'", dir))),
         })
     }
 }
@@ -1,25 +1,50 @@
-pub fn solve() {
-    let input = include_str!("../input/day13");
-    let earliest: u64 = input.lines().next().unwrap().parse().unwrap();
-    let busses: Vec<Option<u64>> = input
-        .lines()
-        .nth(1)
-        .unwrap()
-        .split(',')
-        .map(|s| {
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution};
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u32 = 13;
+
+    type Input = (u64, Vec<Option<u64>>);
+
+    fn parse(&self, input: &str) -> Result<(u64, Vec<Option<u64>>), ParseError> {
+        let mut lines = parse::lines(input);
+        let earliest = lines
+            .next()
+            .ok_or_else(|| parse::missing(input, "a timestamp"))?
+            .parse_number()?;
+
+        let line = lines
+            .next()
+            .ok_or_else(|| parse::missing(input, "a list of busses"))?;
+        let mut busses = Vec::new();
+
+        for s in line.text.split(',') {
             if s == "x" {
-                None
+                busses.push(None);
             } else {
-                Some(s.parse().unwrap())
+                match parse::number(line.text, s) {
+                    Ok(0) => return Err(line.error_at(s, "bus IDs must be positive")),
+                    Ok(b) => busses.push(Some(b)),
+                    Err(e) => return Err(e.at_line(line)),
+                }
             }
-        })
-        .collect();
+        }
+
+        Ok((earliest, busses))
+    }
 
-    println!("Part 1: {}", part1(earliest, &busses));
-    println!("Part 2: {}", part2(&busses));
+    fn part1(&self, input: &(u64, Vec<Option<u64>>)) -> Answer {
+        part1(input.0, &input.1).into()
+    }
+
+    fn part2(&self, input: &(u64, Vec<Option<u64>>)) -> Answer {
+        part2(&input.1).into()
+    }
 }
 
-fn part1(earliest: u64, busses: &[Option<u64>]) -> u64 {
+pub fn part1(earliest: u64, busses: &[Option<u64>]) -> u64 {
     let mut first_bus_t = earliest + 100000;
     let mut first_bus_id = 0;
 
@@ -44,7 +69,7 @@ fn part1(earliest: u64, busses: &[Option<u64>]) -> u64 {
     wait * first_bus_id
 }
 
-fn part2(busses: &[Option<u64>]) -> u64 {
+pub fn part2(busses: &[Option<u64>]) -> u64 {
     let mut moduli = Vec::new();
     let mut remainders = Vec::new();
 
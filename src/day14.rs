@@ -1,19 +1,30 @@
-use anyhow::{bail, Error};
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution};
 use regex::Regex;
 use std::collections::HashMap;
 use std::str::FromStr;
 
-pub fn solve() {
-    let input: Vec<Instr> = include_str!("../input/day14")
-        .lines()
-        .map(|s| s.parse().unwrap())
-        .collect();
+pub struct Day14;
 
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+impl Solution for Day14 {
+    const DAY: u32 = 14;
+
+    type Input = Vec<Instr>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Instr>, ParseError> {
+        parse::parse_lines(input)
+    }
+
+    fn part1(&self, input: &Vec<Instr>) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(&self, input: &Vec<Instr>) -> Answer {
+        part2(input).into()
+    }
 }
 
-fn part1(program: &[Instr]) -> u64 {
+pub fn part1(program: &[Instr]) -> u64 {
     let mut mem = HashMap::new();
     let mut current_mask = 0;
     let mut current_value = 0;
@@ -34,7 +45,7 @@ fn part1(program: &[Instr]) -> u64 {
     mem.values().sum::<u64>()
 }
 
-fn part2(program: &[Instr]) -> u64 {
+pub fn part2(program: &[Instr]) -> u64 {
     let mut mem = HashMap::new();
     let mut current_mask = 0;
     let mut current_value = 0;
@@ -59,7 +70,7 @@ fn part2(program: &[Instr]) -> u64 {
     mem.values().sum::<u64>()
 }
 
-fn addrs_for_mask(mask: u64) -> Vec<u64> {
+pub fn addrs_for_mask(mask: u64) -> Vec<u64> {
     if mask == 0 {
         return vec![0];
     }
@@ -81,26 +92,31 @@ fn addrs_for_mask(mask: u64) -> Vec<u64> {
 }
 
 #[derive(Debug)]
-enum Instr {
+pub enum Instr {
     Mem { addr: u64, value: u64 },
     Mask { mask: u64, value: u64 },
 }
 
 impl FromStr for Instr {
-    type Err = Error;
+    type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Instr, Error> {
-        let mem_re = Regex::new(r"mem\[(\d+)] = (\d+)").unwrap();
-        let bitmask_re = Regex::new("mask = ([01X]+)").unwrap();
+    fn from_str(s: &str) -> Result<Instr, ParseError> {
+        let mem_re = Regex::new(r"^mem\[(\d+)] = (\d+)$").unwrap();
+        let bitmask_re = Regex::new("^mask = ([01X]+)$").unwrap();
 
         if let Some(caps) = mem_re.captures(s) {
-            let addr = caps.get(1).unwrap().as_str().parse()?;
-            let value = caps.get(2).unwrap().as_str().parse()?;
+            let addr = parse::capture_number(s, &caps, 1)?;
+            let value = parse::capture_number(s, &caps, 2)?;
             return Ok(Instr::Mem { addr, value });
         }
 
         if let Some(caps) = bitmask_re.captures(s) {
             let pattern = caps.get(1).unwrap().as_str();
+
+            if pattern.len() != 36 {
+                return Err(ParseError::new(8, "expected a mask of 36 bits"));
+            }
+
             let mut mask = 0;
             let mut value = 0;
 
@@ -120,6 +136,9 @@ impl FromStr for Instr {
             return Ok(Instr::Mask { mask, value });
         }
 
-        bail!("Invalid instruction");
+        Err(ParseError::new(
+            1,
+            "expected \"mask = <bits>\" or \"mem[<addr>] = <value>\"",
+        ))
     }
 }
@@ -1,25 +1,44 @@
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution};
 use std::collections::HashMap;
 
-pub fn solve() {
-    let input: Vec<u32> = include_str!("../input/day15")
-        .trim()
-        .split(',')
-        .map(|s| s.parse().unwrap())
-        .collect();
+pub struct Day15;
 
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+impl Solution for Day15 {
+    const DAY: u32 = 15;
+
+    type Input = Vec<u32>;
+
+    fn parse(&self, input: &str) -> Result<Vec<u32>, ParseError> {
+        let line = parse::lines(input)
+            .next()
+            .ok_or_else(|| parse::missing(input, "the starting numbers"))?;
+
+        line.text
+            .trim()
+            .split(',')
+            .map(|s| parse::number(line.text, s).map_err(|e| e.at_line(line)))
+            .collect()
+    }
+
+    fn part1(&self, input: &Vec<u32>) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(&self, input: &Vec<u32>) -> Answer {
+        part2(input).into()
+    }
 }
 
-fn part1(input: &[u32]) -> u32 {
+pub fn part1(input: &[u32]) -> u32 {
     play_game(input, 2020)
 }
 
-fn part2(input: &[u32]) -> u32 {
+pub fn part2(input: &[u32]) -> u32 {
     play_game(input, 30000000)
 }
 
-fn play_game(input: &[u32], stop_time: u32) -> u32 {
+pub fn play_game(input: &[u32], stop_time: u32) -> u32 {
     let mut history = HashMap::new();
 
     let mut time = 0;
@@ -1,18 +1,31 @@
-use anyhow::Error;
+use crate::parse::Line;
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution};
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::str::FromStr;
 
-pub fn solve() {
-    let input = include_str!("../input/day16");
+pub struct Day16;
 
-    let notes = parse_input(input);
+impl Solution for Day16 {
+    const DAY: u32 = 16;
 
-    println!("Part 1: {}", part1(&notes));
-    println!("Part 2: {}", part2(&notes));
+    type Input = Notes;
+
+    fn parse(&self, input: &str) -> Result<Notes, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, input: &Notes) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(&self, input: &Notes) -> Answer {
+        part2(input).into()
+    }
 }
 
-fn part1(notes: &Notes) -> u32 {
+pub fn part1(notes: &Notes) -> u32 {
     let mut rate = 0;
 
     for t in &notes.nearby_tickets {
@@ -22,7 +35,7 @@ fn part1(notes: &Notes) -> u32 {
     rate
 }
 
-fn part2(notes: &Notes) -> u64 {
+pub fn part2(notes: &Notes) -> u64 {
     let tickets: Vec<Vec<u32>> = notes
         .nearby_tickets
         .iter()
@@ -95,7 +108,7 @@ fn part2(notes: &Notes) -> u64 {
     result
 }
 
-fn scanning_error_rate(ticket: &[u32], fields: &[Field]) -> u32 {
+pub fn scanning_error_rate(ticket: &[u32], fields: &[Field]) -> u32 {
     let mut rate = 0;
 
     for v in ticket {
@@ -116,48 +129,81 @@ fn scanning_error_rate(ticket: &[u32], fields: &[Field]) -> u32 {
     rate
 }
 
-fn parse_input(input: &str) -> Notes {
-    let sections: Vec<&str> = input.split("\n\n").collect();
+pub fn parse_input(input: &str) -> Result<Notes, ParseError> {
+    let sections = parse::sections(input);
 
-    let fields: Vec<Field> = sections[0].lines().map(|l| l.parse().unwrap()).collect();
+    if sections.len() < 3 {
+        return Err(parse::missing(
+            input,
+            "sections for the fields, your ticket and nearby tickets",
+        ));
+    }
 
-    let your_ticket: Vec<u32> = sections[1]
-        .lines()
-        .nth(1)
-        .unwrap()
-        .split(',')
-        .map(|s| s.parse().unwrap())
-        .collect();
+    let fields: Vec<Field> = sections[0]
+        .iter()
+        .map(|l| l.parse())
+        .collect::<Result<_, _>>()?;
+
+    let ticket = |line: &Line| -> Result<Vec<u32>, ParseError> {
+        let values: Vec<u32> = line
+            .text
+            .split(',')
+            .map(|s| parse::number(line.text, s).map_err(|e| e.at_line(*line)))
+            .collect::<Result<_, _>>()?;
+
+        if values.len() != fields.len() {
+            return Err(line.error(
+                1,
+                format!("expected {} values, found {}", fields.len(), values.len()),
+            ));
+        }
 
-    let nearby_tickets: Vec<Vec<u32>> = sections[2]
-        .lines()
-        .skip(1)
-        .map(|l| l.split(',').map(|s| s.parse().unwrap()).collect())
-        .collect();
+        Ok(values)
+    };
+
+    let header = |section: &[Line], name: &str| -> Result<(), ParseError> {
+        if section[0].text == name {
+            Ok(())
+        } else {
+            Err(section[0].error(1, format!("expected \"{}\"", name)))
+        }
+    };
 
-    Notes {
+    header(&sections[1], "your ticket:")?;
+    let your_ticket = match sections[1].get(1) {
+        Some(line) => ticket(line)?,
+        None => return Err(sections[1][0].error(1, "expected a ticket after this line")),
+    };
+
+    header(&sections[2], "nearby tickets:")?;
+    let nearby_tickets: Vec<Vec<u32>> = sections[2][1..]
+        .iter()
+        .map(ticket)
+        .collect::<Result<_, _>>()?;
+
+    Ok(Notes {
         fields,
         your_ticket,
         nearby_tickets,
-    }
+    })
 }
 
-struct Notes {
-    fields: Vec<Field>,
-    your_ticket: Vec<u32>,
-    nearby_tickets: Vec<Vec<u32>>,
+pub struct Notes {
+    pub fields: Vec<Field>,
+    pub your_ticket: Vec<u32>,
+    pub nearby_tickets: Vec<Vec<u32>>,
 }
 
 #[derive(Debug)]
-struct Field {
-    name: String,
-    spans: Vec<(u32, u32)>,
+pub struct Field {
+    pub name: String,
+    pub spans: Vec<(u32, u32)>,
 }
 
 impl Field {
-    fn is_valid(&self, value: u32) -> bool {
+    pub fn is_valid(&self, value: u32) -> bool {
         for s in &self.spans {
-            if value >= s.0 && value <= s.1 {
+            if (s.0..=s.1).contains(&value) {
                 return true;
             }
         }
@@ -165,7 +211,7 @@ impl Field {
         false
     }
 
-    fn all_valid(&self, values: &[u32]) -> bool {
+    pub fn all_valid(&self, values: &[u32]) -> bool {
         for &v in values {
             if !self.is_valid(v) {
                 return false;
@@ -177,19 +223,19 @@ impl Field {
 }
 
 impl FromStr for Field {
-    type Err = Error;
+    type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Field, Error> {
+    fn from_str(s: &str) -> Result<Field, ParseError> {
         lazy_static! {
             static ref RE: Regex = Regex::new(r"^([^:]+): (\d+)-(\d+) or (\d+)-(\d+)$").unwrap();
         }
 
-        let caps = RE.captures(s).unwrap();
-        let name = caps.get(1).unwrap().as_str().to_string();
-        let min1 = caps.get(2).unwrap().as_str().parse()?;
-        let max1 = caps.get(3).unwrap().as_str().parse()?;
-        let min2 = caps.get(4).unwrap().as_str().parse()?;
-        let max2 = caps.get(5).unwrap().as_str().parse()?;
+        let caps = parse::captures(&RE, s, "a field like \"class: 1-3 or 5-7\"")?;
+        let name = caps[1].to_string();
+        let min1 = parse::capture_number(s, &caps, 2)?;
+        let max1 = parse::capture_number(s, &caps, 3)?;
+        let min2 = parse::capture_number(s, &caps, 4)?;
+        let max2 = parse::capture_number(s, &caps, 5)?;
 
         Ok(Field {
             name,
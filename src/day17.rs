@@ -1,140 +1,136 @@
-use std::collections::{HashMap, HashSet};
-use std::hash::Hash;
-use std::ops::Add;
+use crate::automaton::{Automaton, CellSet, Lattice, MirroredLattice, Rule};
+use crate::grid::{self, Grid};
+use crate::parse::ParseError;
+use crate::point::PointN;
+use crate::solution::{Answer, Solution};
+use std::collections::{BTreeSet, HashSet};
 
-pub fn solve() {
-    let input = include_str!("../input/day17");
+pub struct Day17;
 
-    let mut active = HashSet::new();
+impl Solution for Day17 {
+    const DAY: u32 = 17;
 
-    for (y, l) in input.lines().enumerate() {
-        for (x, s) in l.chars().enumerate() {
-            let pos = (x as i32, y as i32);
+    type Input = HashSet<(i32, i32)>;
 
-            if s == '#' {
-                active.insert(pos);
-            }
-        }
+    fn parse(&self, input: &str) -> Result<HashSet<(i32, i32)>, ParseError> {
+        let slice = grid::parse_bool(input)?;
+
+        Ok(slice
+            .iter()
+            .filter(|&(_, &active)| active)
+            .map(|((x, y), _)| (x as i32, y as i32))
+            .collect())
     }
 
-    println!("Part 1: {}", part1(&active));
-    println!("Part 2: {}", part2(&active));
-}
+    fn part1(&self, input: &HashSet<(i32, i32)>) -> Answer {
+        part1(input).into()
+    }
 
-fn part1(active: &HashSet<(i32, i32)>) -> usize {
-    simulate::<Point3D>(active)
+    fn part2(&self, input: &HashSet<(i32, i32)>) -> Answer {
+        part2(input).into()
+    }
 }
 
-fn part2(active: &HashSet<(i32, i32)>) -> usize {
-    simulate::<Point4D>(active)
+pub fn part1(slice: &HashSet<(i32, i32)>) -> usize {
+    simulate_mirrored::<3>(slice, CYCLES)
 }
 
-fn simulate<P: Point>(active: &HashSet<(i32, i32)>) -> usize {
-    let mut active: HashSet<P> = active.iter().map(P::from_2d).collect();
-
-    let deltas = P::deltas();
-
-    for _ in 0..6 {
-        let mut neighbours = HashMap::new();
-
-        for &p in &active {
-            for &dp in &deltas {
-                *neighbours.entry(p + dp).or_insert(0u32) += 1;
-            }
-        }
-
-        let mut new = HashSet::new();
+pub fn part2(slice: &HashSet<(i32, i32)>) -> usize {
+    simulate_mirrored::<4>(slice, CYCLES)
+}
 
-        for (p, num_neighbours) in neighbours {
-            if num_neighbours >= 2 && num_neighbours <= 3 && active.contains(&p) {
-                new.insert(p);
-            }
+/// How many cycles the pocket dimension is booted for.
+pub const CYCLES: usize = 6;
 
-            if num_neighbours == 3 && !active.contains(&p) {
-                new.insert(p);
-            }
-        }
+/// The number of active cubes after booting a D-dimensional pocket dimension
+/// for `cycles` cycles.
+pub fn simulate<const D: usize>(slice: &HashSet<(i32, i32)>, cycles: usize) -> usize {
+    let mut cubes = cubes::<D>(slice);
+    cubes.run(cycles);
+    cubes.population()
+}
 
-        active = new;
-    }
+/// Conway's Game of Life in D dimensions, starting from the active cubes in a
+/// 2D slice where every other coordinate is 0.
+pub fn cubes<const D: usize>(slice: &HashSet<(i32, i32)>) -> Automaton<Lattice<D>> {
+    let active = slice.iter().map(|&(x, y)| PointN::from_2d(x, y));
 
-    active.len()
+    Automaton::new(Lattice::<D>::moore(), Rule::new(&[3], &[2, 3]), active)
 }
 
-trait Point: Sized + Add<Output = Self> + Hash + Eq + Copy {
-    fn from_2d(p: &(i32, i32)) -> Self;
-    fn deltas() -> Vec<Self>;
+/// The same as `simulate`, but much faster. The initial slice is at 0 in
+/// every dimension after the first two, so the pocket dimension stays
+/// symmetric in them and only the orthant where they are not negative needs
+/// to be simulated.
+pub fn simulate_mirrored<const D: usize>(slice: &HashSet<(i32, i32)>, cycles: usize) -> usize {
+    let active = slice.iter().map(|&(x, y)| PointN::from_2d(x, y));
+
+    let mut cubes = Automaton::new(
+        MirroredLattice::<D>::new(2),
+        Rule::new(&[3], &[2, 3]),
+        active,
+    );
+    cubes.run(cycles);
+    cubes.population()
 }
 
-#[derive(Hash, Eq, PartialEq, Copy, Clone)]
-struct Point3D(i32, i32, i32);
+/// Draw each 2D slice through the active cubes, like the puzzle does.
+pub fn render<const D: usize>(active: &CellSet<PointN<D>>) -> String {
+    let (mut min, mut max) = ([i32::MAX; 2], [i32::MIN; 2]);
 
-impl Point for Point3D {
-    fn from_2d(p: &(i32, i32)) -> Self {
-        Point3D(p.0, p.1, 0)
-    }
-
-    fn deltas() -> Vec<Self> {
-        let mut d = Vec::new();
-
-        for &x in &[-1, 0, 1] {
-            for &y in &[-1, 0, 1] {
-                for &z in &[-1, 0, 1] {
-                    if x != 0 || y != 0 || z != 0 {
-                        d.push(Point3D(x, y, z));
-                    }
-                }
-            }
+    for p in active {
+        for i in 0..2 {
+            min[i] = min[i].min(p[i]);
+            max[i] = max[i].max(p[i]);
         }
-
-        d
-    }
-}
-
-impl Add<Point3D> for Point3D {
-    type Output = Point3D;
-
-    fn add(self, rhs: Point3D) -> Point3D {
-        Point3D(self.0 + rhs.0, self.1 + rhs.1, self.2 + rhs.2)
     }
-}
-
-#[derive(Hash, Eq, PartialEq, Copy, Clone)]
-struct Point4D(i32, i32, i32, i32);
 
-impl Point for Point4D {
-    fn from_2d(p: &(i32, i32)) -> Self {
-        Point4D(p.0, p.1, 0, 0)
+    let slices: BTreeSet<&[i32]> = active.iter().map(|p| &p.coords()[2..]).collect();
+    let mut s = String::new();
+
+    for slice in slices {
+        let names = ["z", "w"];
+        let coords: Vec<String> = slice
+            .iter()
+            .enumerate()
+            .map(|(i, c)| match names.get(i) {
+                Some(name) => format!("{}={}", name, c),
+                None => format!("d{}={}", i + 3, c),
+            })
+            .collect();
+
+        let width = (max[0] - min[0] + 1) as usize;
+        let height = (max[1] - min[1] + 1) as usize;
+        let grid = Grid::from_fn(width, height, |x, y| {
+            let mut p = PointN::<D>::from_2d(min[0] + x as i32, min[1] + y as i32);
+            p.0[2..].copy_from_slice(slice);
+            active.contains(&p)
+        });
+
+        s += &format!("{}\n{}\n", coords.join(", "), grid);
     }
 
-    fn deltas() -> Vec<Self> {
-        let mut d = Vec::new();
-
-        for &x in &[-1, 0, 1] {
-            for &y in &[-1, 0, 1] {
-                for &z in &[-1, 0, 1] {
-                    for &w in &[-1, 0, 1] {
-                        if x != 0 || y != 0 || z != 0 || w != 0 {
-                            d.push(Point4D(x, y, z, w));
-                        }
-                    }
-                }
-            }
-        }
-
-        d
-    }
+    s
 }
 
-impl Add<Point4D> for Point4D {
-    type Output = Point4D;
-
-    fn add(self, rhs: Point4D) -> Point4D {
-        Point4D(
-            self.0 + rhs.0,
-            self.1 + rhs.1,
-            self.2 + rhs.2,
-            self.3 + rhs.3,
-        )
+#[test]
+fn test_higher_dimensions() {
+    let slice = Day17.parse(".#.\n..#\n###\n").unwrap();
+
+    assert_eq!(simulate::<2>(&slice, 4), 5);
+    assert_eq!(simulate::<3>(&slice, 1), 11);
+    assert_eq!(simulate::<4>(&slice, 1), 29);
+
+    for cycles in 0..=3 {
+        assert_eq!(
+            simulate_mirrored::<3>(&slice, cycles),
+            simulate::<3>(&slice, cycles)
+        );
+        assert_eq!(
+            simulate_mirrored::<4>(&slice, cycles),
+            simulate::<4>(&slice, cycles)
+        );
     }
+
+    assert_eq!(simulate_mirrored::<5>(&slice, CYCLES), 5760);
 }
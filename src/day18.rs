@@ -1,13 +1,30 @@
+use crate::parse::Line;
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution};
+use lazy_static::lazy_static;
 use regex::Regex;
 
-pub fn solve() {
-    let input = include_str!("../input/day18");
+pub struct Day18;
 
-    println!("Part 1: {}", part1(input));
-    println!("Part 2: {}", part2(input));
+impl Solution for Day18 {
+    const DAY: u32 = 18;
+
+    type Input = Vec<Vec<Token>>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Vec<Token>>, ParseError> {
+        parse::lines(input).map(lex).collect()
+    }
+
+    fn part1(&self, input: &Vec<Vec<Token>>) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(&self, input: &Vec<Vec<Token>>) -> Answer {
+        part2(input).into()
+    }
 }
 
-fn part1(input: &str) -> i64 {
+pub fn part1(lines: &[Vec<Token>]) -> i64 {
     fn get_prec(op: BinOp) -> u32 {
         match op {
             BinOp::Add => 1,
@@ -15,10 +32,10 @@ fn part1(input: &str) -> i64 {
         }
     }
 
-    sum_lines(input, get_prec)
+    sum_lines(lines, get_prec)
 }
 
-fn part2(input: &str) -> i64 {
+pub fn part2(lines: &[Vec<Token>]) -> i64 {
     fn get_prec(op: BinOp) -> u32 {
         match op {
             BinOp::Add => 2,
@@ -26,21 +43,21 @@ fn part2(input: &str) -> i64 {
         }
     }
 
-    sum_lines(input, get_prec)
+    sum_lines(lines, get_prec)
 }
 
-fn sum_lines(input: &str, get_prec: fn(BinOp) -> u32) -> i64 {
+pub fn sum_lines(lines: &[Vec<Token>], get_prec: fn(BinOp) -> u32) -> i64 {
     let mut sum = 0;
 
-    for line in input.lines() {
-        let expr = parse(line, get_prec);
+    for tokens in lines {
+        let expr = parse(tokens, get_prec);
         sum += evaluate(&expr);
     }
 
     sum
 }
 
-fn evaluate(expr: &Expr) -> i64 {
+pub fn evaluate(expr: &Expr) -> i64 {
     match expr {
         Expr::Literal { value } => *value,
         Expr::Binop { op, lhs, rhs } => {
@@ -54,30 +71,72 @@ fn evaluate(expr: &Expr) -> i64 {
     }
 }
 
-fn parse(s: &str, get_prec: fn(BinOp) -> u32) -> Expr {
-    let tokens = lex(s);
+/// Parse a line of tokens, which must have come from `lex`.
+pub fn parse(tokens: &[Token], get_prec: fn(BinOp) -> u32) -> Expr {
     let mut pos = 0;
-    parse_expr(&tokens, &mut pos, 0, get_prec)
+    parse_expr(tokens, &mut pos, 0, get_prec)
 }
 
-fn lex(s: &str) -> Vec<Token> {
-    let re = Regex::new(r"(\d+|[+*()])").unwrap();
+/// Split a line into tokens, checking that they form a valid expression.
+pub fn lex(line: Line) -> Result<Vec<Token>, ParseError> {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"\d+|[+*()]|[^\s]").unwrap();
+    }
 
     let mut tokens = Vec::new();
+    let mut depth = 0;
+    let mut expect_atom = true;
 
-    for m in re.find_iter(s) {
+    for m in RE.find_iter(line.text) {
+        let column = m.start() + 1;
         let tok = match m.as_str() {
             "+" => Token::BinOp(BinOp::Add),
             "*" => Token::BinOp(BinOp::Mul),
             "(" => Token::LParen,
             ")" => Token::RParen,
-            s => Token::Literal(s.parse().unwrap()),
+            s if s.starts_with(|c: char| c.is_ascii_digit()) => {
+                Token::Literal(parse::number(line.text, s).map_err(|e| e.at_line(line))?)
+            }
+            s => return Err(line.error(column, format!("unexpected '{}'", s))),
         };
 
+        let ok = match tok {
+            Token::Literal(_) | Token::LParen => expect_atom,
+            Token::BinOp(_) | Token::RParen => !expect_atom,
+        };
+
+        if !ok {
+            let expected = if expect_atom {
+                "a number or '('"
+            } else {
+                "an operator or ')'"
+            };
+            return Err(line.error(
+                column,
+                format!("expected {}, found '{}'", expected, m.as_str()),
+            ));
+        }
+
+        match tok {
+            Token::LParen => depth += 1,
+            Token::RParen if depth == 0 => return Err(line.error(column, "unmatched ')'")),
+            Token::RParen => depth -= 1,
+            _ => {}
+        }
+
+        expect_atom = matches!(tok, Token::BinOp(_) | Token::LParen);
         tokens.push(tok);
     }
 
-    tokens
+    if expect_atom {
+        return Err(line.error(line.text.len() + 1, "expected a number or '('"));
+    }
+
+    if depth > 0 {
+        return Err(line.error(line.text.len() + 1, "expected ')'"));
+    }
+
+    Ok(tokens)
 }
 
 fn parse_expr(tokens: &[Token], pos: &mut usize, prec: u32, get_prec: fn(BinOp) -> u32) -> Expr {
@@ -139,7 +198,7 @@ fn parse_atom(tokens: &[Token], pos: &mut usize, get_prec: fn(BinOp) -> u32) ->
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
-enum Token {
+pub enum Token {
     Literal(i64),
     BinOp(BinOp),
     LParen,
@@ -147,7 +206,7 @@ enum Token {
 }
 
 #[derive(Debug)]
-enum Expr {
+pub enum Expr {
     Literal {
         value: i64,
     },
@@ -160,7 +219,7 @@ enum Expr {
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
-enum BinOp {
+pub enum BinOp {
     Add,
     Mul,
 }
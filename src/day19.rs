@@ -1,22 +1,34 @@
-use anyhow::{bail, Error};
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution};
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::collections::HashMap;
 use std::str::FromStr;
 
-pub fn solve() {
-    let input = include_str!("../input/day19");
+pub struct Day19;
 
-    let (messages, rules) = parse(input);
+impl Solution for Day19 {
+    const DAY: u32 = 19;
 
-    println!("Part 1: {}", part1(&rules, &messages));
-    println!("Part 2: {}", part2(&rules, &messages));
+    type Input = (Vec<String>, HashMap<u32, Rule>);
+
+    fn parse(&self, input: &str) -> Result<(Vec<String>, HashMap<u32, Rule>), ParseError> {
+        parse(input)
+    }
+
+    fn part1(&self, input: &(Vec<String>, HashMap<u32, Rule>)) -> Answer {
+        part1(&input.1, &input.0).into()
+    }
+
+    fn part2(&self, input: &(Vec<String>, HashMap<u32, Rule>)) -> Answer {
+        part2(&input.1, &input.0).into()
+    }
 }
 
-fn part1(rules: &HashMap<u32, Rule>, messages: &[&str]) -> u32 {
+pub fn part1(rules: &HashMap<u32, Rule>, messages: &[String]) -> u32 {
     let mut count = 0;
 
-    for &m in messages {
+    for m in messages {
         if is_match(m, rules) {
             count += 1;
         }
@@ -25,7 +37,7 @@ fn part1(rules: &HashMap<u32, Rule>, messages: &[&str]) -> u32 {
     count
 }
 
-fn part2(rules: &HashMap<u32, Rule>, messages: &[&str]) -> u32 {
+pub fn part2(rules: &HashMap<u32, Rule>, messages: &[String]) -> u32 {
     let mut count = 0;
 
     // 0: 8 11
@@ -34,7 +46,7 @@ fn part2(rules: &HashMap<u32, Rule>, messages: &[&str]) -> u32 {
     //
     // => 0: 42{n} 31{m}   where n > m > 0
 
-    for &message in messages {
+    for message in messages {
         let message: Vec<char> = message.chars().collect();
         let rule42 = &rules[&42];
         let rule31 = &rules[&31];
@@ -63,26 +75,58 @@ fn part2(rules: &HashMap<u32, Rule>, messages: &[&str]) -> u32 {
     count
 }
 
-fn parse(input: &str) -> (Vec<&str>, HashMap<u32, Rule>) {
-    let mut sections = input.split("\n\n");
-    let rules_str = sections.next().unwrap();
-    let messages_str = sections.next().unwrap();
+pub fn parse(input: &str) -> Result<(Vec<String>, HashMap<u32, Rule>), ParseError> {
+    let sections = parse::sections(input);
+
+    if sections.len() < 2 {
+        return Err(parse::missing(
+            input,
+            "a blank line followed by the messages",
+        ));
+    }
 
     let mut rules = HashMap::new();
-    let messages: Vec<&str> = messages_str.trim().split('\n').collect();
+    let mut refs = Vec::new();
+
+    for &line in &sections[0] {
+        let (id, body) = line
+            .text
+            .split_once(':')
+            .ok_or_else(|| line.error(1, "expected a rule like \"0: 1 2\""))?;
+        let rule_id: u32 = parse::number(line.text, id).map_err(|e| e.at_line(line))?;
+        let body = body.trim();
+        let rule: Rule = body
+            .parse()
+            .map_err(|e: ParseError| e.offset(parse::column(line.text, body) - 1).at_line(line))?;
+
+        rule.refs(&mut |r| refs.push((r, line)));
+        rules.insert(rule_id, rule);
+    }
 
-    for line in rules_str.lines() {
-        let mut parts = line.split(':');
-        let rule_id: u32 = parts.next().unwrap().parse().unwrap();
-        let rule: Rule = parts.next().unwrap().parse().unwrap();
+    for (r, line) in refs {
+        if !rules.contains_key(&r) {
+            return Err(line.error(1, format!("rule {} does not exist", r)));
+        }
+    }
 
-        rules.insert(rule_id, rule);
+    if !rules.contains_key(&0) {
+        return Err(sections[0][0].error(1, "there is no rule 0"));
+    }
+
+    let mut messages = Vec::new();
+
+    for line in &sections[1] {
+        if let Some(i) = line.text.find(|c| c != 'a' && c != 'b') {
+            return Err(line.error(i + 1, "messages can only contain 'a' and 'b'"));
+        }
+
+        messages.push(line.text.to_string());
     }
 
-    (messages, rules)
+    Ok((messages, rules))
 }
 
-fn is_match(message: &str, rules: &HashMap<u32, Rule>) -> bool {
+pub fn is_match(message: &str, rules: &HashMap<u32, Rule>) -> bool {
     let message: Vec<char> = message.chars().collect();
     let rule = &rules[&0];
     let mut pos = 0;
@@ -132,17 +176,31 @@ fn is_match_impl(
 }
 
 #[derive(Debug)]
-enum Rule {
+pub enum Rule {
     Literal(char),
     Ref(u32),
     Alternation(Box<Rule>, Box<Rule>),
     Concatenation(Box<Rule>, Box<Rule>),
 }
 
+impl Rule {
+    /// Call `f` with the ID of each rule referenced by this one.
+    fn refs(&self, f: &mut impl FnMut(u32)) {
+        match self {
+            Rule::Literal(_) => {}
+            Rule::Ref(r) => f(*r),
+            Rule::Alternation(lhs, rhs) | Rule::Concatenation(lhs, rhs) => {
+                lhs.refs(f);
+                rhs.refs(f);
+            }
+        }
+    }
+}
+
 impl FromStr for Rule {
-    type Err = Error;
+    type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Rule, Error> {
+    fn from_str(s: &str) -> Result<Rule, ParseError> {
         lazy_static! {
             static ref LITERAL_RE: Regex = Regex::new(r#"^"(a|b)"$"#).unwrap();
             static ref REF_RE: Regex = Regex::new(r"^(\d+)$").unwrap();
@@ -155,18 +213,18 @@ impl FromStr for Rule {
         let s = s.trim();
 
         if let Some(caps) = LITERAL_RE.captures(s) {
-            let lit = caps.get(1).unwrap().as_str().parse().unwrap();
+            let lit = if &caps[1] == "a" { 'a' } else { 'b' };
             return Ok(Rule::Literal(lit));
         }
 
         if let Some(caps) = REF_RE.captures(s) {
-            let r = caps.get(1).unwrap().as_str().parse().unwrap();
+            let r = parse::capture_number(s, &caps, 1)?;
             return Ok(Rule::Ref(r));
         }
 
         if let Some(caps) = CONCAT_RE.captures(s) {
-            let a = caps.get(1).unwrap().as_str().parse().unwrap();
-            let b = caps.get(2).unwrap().as_str().parse().unwrap();
+            let a = parse::capture_number(s, &caps, 1)?;
+            let b = parse::capture_number(s, &caps, 2)?;
             return Ok(Rule::Concatenation(
                 Box::new(Rule::Ref(a)),
                 Box::new(Rule::Ref(b)),
@@ -174,16 +232,16 @@ impl FromStr for Rule {
         }
 
         if let Some(caps) = TRP_CONCAT_RE.captures(s) {
-            let a = caps.get(1).unwrap().as_str().parse().unwrap();
-            let b = caps.get(2).unwrap().as_str().parse().unwrap();
-            let c = caps.get(3).unwrap().as_str().parse().unwrap();
+            let a = parse::capture_number(s, &caps, 1)?;
+            let b = parse::capture_number(s, &caps, 2)?;
+            let c = parse::capture_number(s, &caps, 3)?;
             let lhs = Rule::Concatenation(Box::new(Rule::Ref(a)), Box::new(Rule::Ref(b)));
             return Ok(Rule::Concatenation(Box::new(lhs), Box::new(Rule::Ref(c))));
         }
 
         if let Some(caps) = ALTERN_RE.captures(s) {
-            let a = caps.get(1).unwrap().as_str().parse().unwrap();
-            let b = caps.get(2).unwrap().as_str().parse().unwrap();
+            let a = parse::capture_number(s, &caps, 1)?;
+            let b = parse::capture_number(s, &caps, 2)?;
             return Ok(Rule::Alternation(
                 Box::new(Rule::Ref(a)),
                 Box::new(Rule::Ref(b)),
@@ -191,24 +249,24 @@ impl FromStr for Rule {
         }
 
         if let Some(caps) = DBL_ALTERN_RE.captures(s) {
-            let a = caps.get(1).unwrap().as_str().parse().unwrap();
-            let b = caps.get(2).unwrap().as_str().parse().unwrap();
-            let c = caps.get(3).unwrap().as_str().parse().unwrap();
-            let d = caps.get(4).unwrap().as_str().parse().unwrap();
+            let a = parse::capture_number(s, &caps, 1)?;
+            let b = parse::capture_number(s, &caps, 2)?;
+            let c = parse::capture_number(s, &caps, 3)?;
+            let d = parse::capture_number(s, &caps, 4)?;
 
             let lhs = Rule::Concatenation(Box::new(Rule::Ref(a)), Box::new(Rule::Ref(b)));
             let rhs = Rule::Concatenation(Box::new(Rule::Ref(c)), Box::new(Rule::Ref(d)));
             return Ok(Rule::Alternation(Box::new(lhs), Box::new(rhs)));
         }
 
-        bail!("Invalid rule");
+        Err(ParseError::new(1, "invalid rule"))
     }
 }
 
 #[test]
 fn part1_example() {
     let input = "0: 4 1 5\n1: 2 3 | 3 2\n2: 4 4 | 5 5\n3: 4 5 | 5 4\n4: \"a\"\n5: \"b\"\n\nababbb\nbababa\nabbbab\naaabbb\naaaabbb\n";
-    let (messages, rules) = parse(input);
+    let (messages, rules) = parse(input).unwrap();
     let result = part1(&rules, &messages);
     assert_eq!(result, 2);
 }
@@ -1,19 +1,30 @@
-use anyhow::Error;
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution};
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::str::FromStr;
 
-pub fn solve() {
-    let input: Vec<Password> = include_str!("../input/day2")
-        .lines()
-        .map(|s| s.parse().unwrap())
-        .collect();
+pub struct Day2;
 
-    println!("Part 1: {}", part1(&input));
-    println!("Part 1: {}", part2(&input));
+impl Solution for Day2 {
+    const DAY: u32 = 2;
+
+    type Input = Vec<Password>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Password>, ParseError> {
+        parse::parse_lines(input)
+    }
+
+    fn part1(&self, input: &Vec<Password>) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(&self, input: &Vec<Password>) -> Answer {
+        part2(input).into()
+    }
 }
 
-fn part1(passwords: &[Password]) -> u32 {
+pub fn part1(passwords: &[Password]) -> u32 {
     let mut valid_count = 0;
 
     for p in passwords {
@@ -27,7 +38,7 @@ fn part1(passwords: &[Password]) -> u32 {
     valid_count
 }
 
-fn part2(passwords: &[Password]) -> u32 {
+pub fn part2(passwords: &[Password]) -> u32 {
     let mut valid_count = 0;
 
     for p in passwords {
@@ -45,29 +56,33 @@ fn part2(passwords: &[Password]) -> u32 {
     valid_count
 }
 
-struct Password {
+pub struct Password {
     // Password policy
-    min: usize,
-    max: usize,
-    letter: char,
+    pub min: usize,
+    pub max: usize,
+    pub letter: char,
 
     // The password
-    password: String,
+    pub password: String,
 }
 
 impl FromStr for Password {
-    type Err = Error;
+    type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Password, Error> {
+    fn from_str(s: &str) -> Result<Password, ParseError> {
         lazy_static! {
             static ref RE: Regex = Regex::new(r"^(\d+)-(\d+) ([a-z]): ([a-z]+)$").unwrap();
         }
 
-        let caps = RE.captures(s).unwrap();
-        let min = caps.get(1).unwrap().as_str().parse()?;
-        let max = caps.get(2).unwrap().as_str().parse()?;
-        let letter = caps.get(3).unwrap().as_str().parse()?;
-        let password = caps.get(4).unwrap().as_str().to_string();
+        let caps = parse::captures(&RE, s, "a policy and password like \"1-3 a: abcde\"")?;
+        let min = parse::capture_number(s, &caps, 1)?;
+        let max = parse::capture_number(s, &caps, 2)?;
+        let letter = caps[3].chars().next().unwrap();
+        let password = caps[4].to_string();
+
+        if min == 0 {
+            return Err(ParseError::new(1, "positions start at 1"));
+        }
 
         Ok(Password {
             min,
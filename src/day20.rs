@@ -1,319 +1,605 @@
 #![allow(unused)]
 
-use anyhow::Error;
+use crate::grid::{self, Grid};
+use crate::parse::Line;
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Output, Part, Settings, Solution};
+use anyhow::{bail, Error};
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 
-const SIZE_TILES: usize = 12;
+pub struct Day20;
 
-pub fn solve() {
-    let input = include_str!("../input/day20");
+impl Solution for Day20 {
+    const DAY: u32 = 20;
 
-    let tiles: Vec<Tile> = input
-        .trim()
-        .split("\n\n")
-        .map(|s| s.parse().unwrap())
-        .collect();
+    type Input = Vec<Tile>;
 
-    assert_eq!(tiles.len(), SIZE_TILES * SIZE_TILES);
+    fn parse(&self, input: &str) -> Result<Vec<Tile>, ParseError> {
+        let sections = parse::sections(input);
+        let tiles: Vec<Tile> = sections
+            .iter()
+            .map(|lines| Tile::from_lines(lines))
+            .collect::<Result<_, _>>()?;
 
-    println!("Part 2: {}", part2(&tiles));
-}
+        let last = match sections.last() {
+            Some(lines) => lines[0],
+            None => return Err(parse::missing(input, "a tile")),
+        };
 
-fn part2(tiles: &[Tile]) -> u64 {
-    let mut border_to_tile_map: HashMap<BorderId, Vec<TileId>> = HashMap::new();
+        if grid_size(tiles.len()).is_none() {
+            return Err(last.error(
+                1,
+                format!(
+                    "there are {} tiles, which cannot make a square",
+                    tiles.len()
+                ),
+            ));
+        }
 
-    for tile in tiles {
-        for &border in &tile.borders {
-            border_to_tile_map.entry(border).or_default().push(tile.id);
+        for (tile, lines) in tiles.iter().zip(&sections) {
+            if tile.image.width() != tiles[0].image.width() {
+                return Err(lines[0].error(
+                    1,
+                    format!(
+                        "tiles must all be the same size, expected {} rows",
+                        tiles[0].image.width() + 2
+                    ),
+                ));
+            }
         }
+
+        Ok(tiles)
     }
 
-    let is_edge = |border| border_to_tile_map[&border].len() == 1;
+    fn run(&self, input: &Vec<Tile>, part: Part, _settings: &Settings) -> Result<Output, Error> {
+        let answer: Answer = match part {
+            Part::One => part1(input)?.into(),
+            Part::Two => part2(input)?.into(),
+        };
 
-    let mut grid = Vec::new();
-    let mut row = Vec::new();
+        Ok(answer.into())
+    }
+}
 
-    //------------------------------------------------------------------------
-    // Find a corner and put it at the start of the row
+/// The number of tiles along each side of a square of `count` tiles.
+pub fn grid_size(count: usize) -> Option<usize> {
+    (1..=count).find(|n| n * n == count)
+}
 
-    let mut corner = None;
+pub fn part1(tiles: &[Tile]) -> Result<u64, Error> {
+    let border_to_tile_map = border_to_tile_map(tiles);
+    let positions = classify(tiles, &border_to_tile_map);
+    let mut corners: Vec<TileId> = tiles
+        .iter()
+        .map(|t| t.id)
+        .filter(|id| positions[id] == Position::Corner)
+        .collect();
 
-    for tile in tiles {
-        let mut edges = 0;
+    // The borders only say which tiles are corners if each one is shared by
+    // at most two tiles. Otherwise, fit the tiles together to find out.
+    let unique = border_to_tile_map.values().all(|ids| ids.len() <= 2);
 
-        for &border in &tile.borders {
-            if is_edge(border) {
-                edges += 1;
-            }
-        }
+    if !unique || corners.len() != tiles.len().min(4) {
+        let grid = assemble(tiles)?;
+        let last = grid.width() - 1;
 
-        assert!(edges <= 2);
+        corners = [(0, 0), (last, 0), (0, last), (last, last)]
+            .iter()
+            .map(|&p| grid[p].tile.id)
+            .collect();
+        corners.sort_unstable();
+        corners.dedup();
+    }
 
-        if edges == 2 {
-            corner = Some(tile);
-            break;
+    Ok(corners.iter().product())
+}
+
+/// The tiles that have each border, which is two tiles for a border between
+/// tiles and one for a border on the edge of the image.
+pub type BorderMap = HashMap<BorderId, Vec<TileId>>;
+
+pub fn border_to_tile_map(tiles: &[Tile]) -> BorderMap {
+    let mut border_to_tile_map: BorderMap = HashMap::new();
+
+    for tile in tiles {
+        for &border in &tile.borders {
+            border_to_tile_map.entry(border).or_default().push(tile.id);
         }
     }
 
-    let mut corner = TransformedTile::from_tile(corner.expect("Could not find a corner").clone());
+    border_to_tile_map
+}
 
-    corner.flip_h();
+/// Where a tile goes in the assembled image.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Position {
+    Corner,
+    Edge,
+    Interior,
+}
 
-    while !is_edge(corner.left_border()) || !is_edge(corner.top_border()) {
-        corner.rotate();
-    }
+/// Classify the tiles by how many of their borders no other tile shares.
+pub fn classify(tiles: &[Tile], border_to_tile_map: &BorderMap) -> HashMap<TileId, Position> {
+    tiles
+        .iter()
+        .map(|tile| {
+            let unmatched = tile
+                .borders
+                .iter()
+                .filter(|b| border_to_tile_map[b].len() == 1)
+                .count();
+
+            let position = match unmatched {
+                0 => Position::Interior,
+                1 => Position::Edge,
+                _ => Position::Corner,
+            };
+
+            (tile.id, position)
+        })
+        .collect()
+}
 
-    row.push(corner);
+pub fn part2(tiles: &[Tile]) -> Result<u64, Error> {
+    let grid = assemble(tiles)?;
+    Ok(roughness(&stitch(&grid), &SEA_MONSTER.parse().unwrap()))
+}
 
-    //------------------------------------------------------------------------
-    // Connect up the pieces in the first row
+/// The full image, made from the tiles without their borders.
+pub fn stitch(grid: &Grid<TransformedTile>) -> Grid<bool> {
+    let tile_size = grid[(0, 0)].tile.image.width();
 
-    for c in 1..SIZE_TILES {
-        let border = row[c - 1].right_border();
-        let prev_id = row[c - 1].tile.id;
+    Grid::from_fn(
+        tile_size * grid.width(),
+        tile_size * grid.height(),
+        |x, y| grid[(x / tile_size, y / tile_size)].sample(x % tile_size, y % tile_size),
+    )
+}
 
-        let mut piece = None;
+/// The squares of the image that are part of a sea monster.
+pub fn sea_monsters(image: &Grid<bool>) -> HashSet<(usize, usize)> {
+    let pattern: Pattern = SEA_MONSTER.parse().unwrap();
 
-        for tile in tiles {
-            if tile.borders.contains(&border) && tile.id != prev_id {
-                piece = Some(tile);
-                break;
-            }
+    pattern
+        .find(image)
+        .iter()
+        .flat_map(|m| pattern.squares(m))
+        .collect()
+}
+
+/// Draw the image like the puzzle does, with `O` for the `highlighted`
+/// squares.
+pub fn render(image: &Grid<bool>, highlighted: &HashSet<(usize, usize)>) -> String {
+    Grid::from_fn(image.width(), image.height(), |x, y| {
+        if highlighted.contains(&(x, y)) {
+            'O'
+        } else if image[(x, y)] {
+            '#'
+        } else {
+            '.'
         }
+    })
+    .render(|&c| c)
+}
 
-        let mut piece =
-            TransformedTile::from_tile(piece.expect("Could not find connecting piece").clone());
+/// The image as a plain PBM bitmap, with `#` squares in black.
+pub fn to_pbm(image: &Grid<bool>) -> String {
+    let header = format!("P1\n{} {}\n", image.width(), image.height());
+    header + &raster(&image.map(|&b| if b { '1' } else { '0' }))
+}
 
-        while piece.left_border() != border {
-            piece.rotate();
+/// The image as a plain PGM greymap, with `.` squares in black, `#` squares in
+/// grey and the `highlighted` squares in white.
+pub fn to_pgm(image: &Grid<bool>, highlighted: &HashSet<(usize, usize)>) -> String {
+    let levels = Grid::from_fn(image.width(), image.height(), |x, y| {
+        if highlighted.contains(&(x, y)) {
+            '2'
+        } else if image[(x, y)] {
+            '1'
+        } else {
+            '0'
         }
+    });
+
+    let header = format!("P2\n{} {}\n2\n", image.width(), image.height());
+    header + &raster(&levels)
+}
+
+/// The pixels of a plain PBM or PGM file. Lines in these files should not be
+/// longer than 70 characters, so long rows are wrapped.
+fn raster(pixels: &Grid<char>) -> String {
+    let mut s = String::new();
+
+    for y in 0..pixels.height() {
+        let row: Vec<char> = (0..pixels.width()).map(|x| pixels[(x, y)]).collect();
 
-        if !is_edge(piece.top_border()) {
-            piece.flip_v();
+        for chunk in row.chunks(35) {
+            let line: Vec<String> = chunk.iter().map(|c| c.to_string()).collect();
+            s += &line.join(" ");
+            s.push('\n');
         }
+    }
+
+    s
+}
+
+/// Which tile is where, and how it was turned. Each tile is shown as its ID,
+/// then `:` and how many degrees it was rotated clockwise, then `f` if it was
+/// flipped left to right before that.
+pub fn placements(grid: &Grid<TransformedTile>) -> String {
+    let labels = grid.map(|t| {
+        format!(
+            "{}:{}{}",
+            t.tile.id,
+            90 * (t.rotation % 4),
+            if t.flipped { "f" } else { "" }
+        )
+    });
+    let width = labels.cells().iter().map(|l| l.len()).max().unwrap_or(0);
+
+    let mut s = String::new();
+
+    for y in 0..labels.height() {
+        let row: Vec<String> = (0..labels.width())
+            .map(|x| format!("{:width$}", labels[(x, y)], width = width))
+            .collect();
 
-        row.push(piece);
+        s += row.join("  ").trim_end();
+        s.push('\n');
     }
 
-    grid.push(row);
+    s
+}
 
-    //------------------------------------------------------------------------
-    // Connect up the pieces in the remaining rows
+pub const SEA_MONSTER: &str = "                  # \n#    ##    ##    ###\n #  #  #  #  #  #   ";
 
-    for r in 1..SIZE_TILES {
-        row = Vec::new();
+/// The number of `#` squares in the image that are not part of any match of
+/// the pattern.
+pub fn roughness(image: &Grid<bool>, pattern: &Pattern) -> u64 {
+    let covered: HashSet<(usize, usize)> = pattern
+        .find(image)
+        .iter()
+        .flat_map(|m| pattern.squares(m))
+        .collect();
 
-        for c in 0..SIZE_TILES {
-            let border = grid[r - 1][c].bottom_border();
-            let prev_id = grid[r - 1][c].tile.id;
+    (image.count(|&b| b) - covered.len()) as u64
+}
 
-            let mut piece = None;
+/// A picture to search for, where `#` squares must be set and every other
+/// square can be anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    grid: Grid<bool>,
+}
 
-            for tile in tiles {
-                if tile.borders.contains(&border) && tile.id != prev_id {
-                    piece = Some(tile);
-                    break;
-                }
-            }
+/// Where a pattern was found. The pattern was flipped (if `flipped`) and then
+/// rotated clockwise by `rotation` quarter turns, and its top left corner is
+/// at `(x, y)` in the image.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Match {
+    pub x: usize,
+    pub y: usize,
+    pub rotation: usize,
+    pub flipped: bool,
+}
 
-            let mut piece =
-                TransformedTile::from_tile(piece.expect("Could not find connecting piece").clone());
+impl FromStr for Pattern {
+    type Err = ParseError;
 
-            while piece.top_border() != border {
-                piece.rotate();
-            }
+    fn from_str(s: &str) -> Result<Pattern, ParseError> {
+        let lines: Vec<Line> = parse::lines(s).collect();
+        let width = lines.iter().map(|l| l.text.len()).max().unwrap_or(0);
 
-            if c == 0 {
-                if !is_edge(piece.left_border()) {
-                    piece.flip_h();
-                    assert!(is_edge(piece.left_border()));
-                }
-            } else {
-                let border = row[c - 1].right_border();
-                if piece.left_border() != border {
-                    piece.flip_h();
-                    assert!(piece.left_border() == border);
-                }
+        for line in &lines {
+            if let Some((i, c)) = line.text.char_indices().find(|&(_, c)| !c.is_ascii()) {
+                return Err(line.error(i + 1, format!("unexpected '{}'", c)));
             }
+        }
+
+        let grid = Grid::from_fn(width, lines.len(), |x, y| {
+            lines[y].text.as_bytes().get(x) == Some(&b'#')
+        });
 
-            row.push(piece);
+        if grid.count(|&b| b) == 0 {
+            return Err(ParseError::new(1, "the pattern has no # squares"));
         }
 
-        grid.push(row);
+        Ok(Pattern { grid })
     }
+}
 
-    //------------------------------------------------------------------------
-    // Check that it is assembled correctly
+impl Pattern {
+    /// Every match in every orientation, including matches that overlap.
+    /// Orientations that look the same as an earlier one are skipped, so
+    /// each set of squares is only found once.
+    pub fn find(&self, image: &Grid<bool>) -> Vec<Match> {
+        let mut seen = Vec::new();
+        let mut matches = Vec::new();
 
-    for i in 0..SIZE_TILES {
-        assert!(is_edge(grid[0][i].top_border()));
-        assert!(is_edge(grid[i][SIZE_TILES - 1].right_border()));
-        assert!(is_edge(grid[SIZE_TILES - 1][i].bottom_border()));
-        assert!(is_edge(grid[i][0].left_border()));
-    }
+        for flipped in [false, true] {
+            for rotation in 0..4 {
+                let pattern = self.grid.transform(rotation, flipped);
 
-    for r in 0..(SIZE_TILES - 1) {
-        for c in 0..(SIZE_TILES - 1) {
-            assert_eq!(grid[r][c].right_border(), grid[r][c + 1].left_border());
-            assert_eq!(grid[r][c].bottom_border(), grid[r + 1][c].top_border());
+                if seen.contains(&pattern) {
+                    continue;
+                }
+
+                let set: Vec<(usize, usize)> = pattern
+                    .iter()
+                    .filter(|&(_, &b)| b)
+                    .map(|(p, _)| p)
+                    .collect();
+
+                for y in 0..(image.height() + 1).saturating_sub(pattern.height()) {
+                    for x in 0..(image.width() + 1).saturating_sub(pattern.width()) {
+                        if set.iter().all(|&(dx, dy)| image[(x + dx, y + dy)]) {
+                            matches.push(Match {
+                                x,
+                                y,
+                                rotation,
+                                flipped,
+                            });
+                        }
+                    }
+                }
+
+                seen.push(pattern);
+            }
         }
-    }
 
-    //------------------------------------------------------------------------
-    // Make the full image
+        matches
+    }
 
-    let mut image = vec![vec![false; 8 * SIZE_TILES]; 8 * SIZE_TILES];
+    /// The squares of the image that are set in a match.
+    pub fn squares(&self, m: &Match) -> Vec<(usize, usize)> {
+        self.grid
+            .transform(m.rotation, m.flipped)
+            .iter()
+            .filter(|&(_, &b)| b)
+            .map(|((dx, dy), _)| (m.x + dx, m.y + dy))
+            .collect()
+    }
+}
 
-    for r in 0..(8 * SIZE_TILES) {
-        for c in 0..(8 * SIZE_TILES) {
-            image[r][c] = grid[r / 8][c / 8].sample(r % 8, c % 8);
-        }
+/// Find the one way to fit the tiles together, or fail if there are none or
+/// several. The 8 ways to turn over or rotate the whole image count as one.
+pub fn assemble(tiles: &[Tile]) -> Result<Grid<TransformedTile>, Error> {
+    let size = match grid_size(tiles.len()) {
+        Some(size) => size,
+        None => bail!(
+            "There are {} tiles, which cannot make a square",
+            tiles.len()
+        ),
+    };
+
+    let mut search = Search::new(tiles, size);
+    search.place();
+
+    match search.solutions.as_slice() {
+        [] => bail!("The tiles cannot be fitted together"),
+        [placed] => Ok(Grid::from_fn(size, size, |x, y| {
+            let p = &search.placements[placed[y * size + x]];
+            TransformedTile {
+                tile: tiles[p.tile].clone(),
+                flipped: p.flipped,
+                rotation: p.rotation,
+            }
+        })),
+        _ => bail!("The tiles can be fitted together in more than one way"),
     }
+}
 
-    //------------------------------------------------------------------------
-    // Search for monsters
-
-    //           1111111111
-    // 01234567890123456789
-    //                   #
-    // #    ##    ##    ###
-    //  #  #  #  #  #  #
-
-    let monster = vec![
-        (0, 18),
-        (1, 0),
-        (1, 5),
-        (1, 6),
-        (1, 11),
-        (1, 12),
-        (1, 17),
-        (1, 18),
-        (1, 19),
-        (2, 1),
-        (2, 4),
-        (2, 7),
-        (2, 10),
-        (2, 13),
-        (2, 16),
-    ];
-
-    for &flipped in &[true, false] {
-        for rotation in 0..4 {
-            let mut monster_count = 0;
-            let new_image = transform_image(&image, rotation, flipped);
-            let mut monster_coords = HashSet::new();
-
-            for mut r in 0..(8 * SIZE_TILES - 2) {
-                for mut c in 0..(8 * SIZE_TILES - 19) {
-                    let mut is_monster = true;
-
-                    for (dr, dc) in &monster {
-                        if !new_image[r + dr][c + dc] {
-                            is_monster = false;
-                            break;
-                        }
-                    }
+/// A tile in one of its 8 orientations, with its borders read left to right
+/// and top to bottom.
+#[derive(Debug, Clone)]
+struct Placement {
+    tile: usize,
+    flipped: bool,
+    rotation: usize,
+    top: BorderId,
+    right: BorderId,
+    bottom: BorderId,
+    left: BorderId,
+}
+
+/// A depth first search that places tiles row by row, trying every placement
+/// that fits the tiles above and to the left.
+struct Search<'a> {
+    tiles: &'a [Tile],
+    size: usize,
+    placements: Vec<Placement>,
+    by_left: HashMap<BorderId, Vec<usize>>,
+    by_top: HashMap<BorderId, Vec<usize>>,
+    used: Vec<bool>,
+    placed: Vec<usize>,
+    solutions: Vec<Vec<usize>>,
+}
 
-                    if is_monster {
-                        for (dr, dc) in &monster {
-                            monster_coords.insert((r + dr, c + dc));
+impl<'a> Search<'a> {
+    fn new(tiles: &'a [Tile], size: usize) -> Search<'a> {
+        let mut placements = Vec::new();
+
+        for (i, tile) in tiles.iter().enumerate() {
+            let len = tile.image.width() + 2;
+
+            for flipped in [false, true] {
+                for rotation in 0..4 {
+                    // Rotating keeps the borders in clockwise order, and
+                    // turning the tile over reverses them
+                    let side = |n: usize| {
+                        if flipped {
+                            reverse_bits(tile.sides[(4 + rotation - n) % 4], len)
+                        } else {
+                            tile.sides[(n + rotation) % 4]
                         }
-                    }
+                    };
+
+                    placements.push(Placement {
+                        tile: i,
+                        flipped,
+                        rotation,
+                        top: side(0),
+                        right: side(1),
+                        bottom: reverse_bits(side(2), len),
+                        left: reverse_bits(side(3), len),
+                    });
                 }
             }
+        }
 
-            if !monster_coords.is_empty() {
-                let mut roughness = 0;
+        let mut by_left: HashMap<BorderId, Vec<usize>> = HashMap::new();
+        let mut by_top: HashMap<BorderId, Vec<usize>> = HashMap::new();
 
-                for mut r in 0..(8 * SIZE_TILES) {
-                    for mut c in 0..(8 * SIZE_TILES) {
-                        if new_image[r][c] && !monster_coords.contains(&(r, c)) {
-                            roughness += 1;
-                        }
-                    }
-                }
+        for (i, p) in placements.iter().enumerate() {
+            by_left.entry(p.left).or_default().push(i);
+            by_top.entry(p.top).or_default().push(i);
+        }
 
-                return roughness;
-            }
+        Search {
+            tiles,
+            size,
+            placements,
+            by_left,
+            by_top,
+            used: vec![false; tiles.len()],
+            placed: Vec::new(),
+            solutions: Vec::new(),
         }
     }
 
-    0
-}
+    /// Place the next tile, and then the rest, until two solutions are found.
+    fn place(&mut self) {
+        let n = self.placed.len();
 
-fn transform_image(image: &Vec<Vec<bool>>, rotation: u32, flipped: bool) -> Vec<Vec<bool>> {
-    let mut new_image = vec![vec![false; 8 * SIZE_TILES]; 8 * SIZE_TILES];
+        if n == self.size * self.size {
+            self.solutions.push(self.placed.clone());
+            return;
+        }
 
-    for r in 0..(8 * SIZE_TILES) {
-        for c in 0..(8 * SIZE_TILES) {
-            let sample = image[r][c];
+        let (x, y) = (n % self.size, n / self.size);
+        let above = (y > 0).then(|| self.placements[self.placed[n - self.size]].bottom);
+        let left = (x > 0).then(|| self.placements[self.placed[n - 1]].right);
 
-            let mut r = r;
-            let mut c = c;
+        let candidates: Vec<usize> = match (left, above) {
+            (Some(left), _) => self.by_left.get(&left).cloned().unwrap_or_default(),
+            (None, Some(above)) => self.by_top.get(&above).cloned().unwrap_or_default(),
+            (None, None) => (0..self.placements.len()).collect(),
+        };
 
-            for _ in 0..rotation {
-                std::mem::swap(&mut r, &mut c);
-                r = 8 * SIZE_TILES - 1 - r;
-            }
+        for i in candidates {
+            if self.fits(x, y, &self.placements[i], above) {
+                let tile = self.placements[i].tile;
 
-            if flipped {
-                c = 8 * SIZE_TILES - 1 - c;
+                self.used[tile] = true;
+                self.placed.push(i);
+                self.place();
+                self.placed.pop();
+                self.used[tile] = false;
+
+                if self.solutions.len() > 1 {
+                    return;
+                }
             }
+        }
+    }
+
+    fn fits(&self, x: usize, y: usize, p: &Placement, above: Option<BorderId>) -> bool {
+        if self.used[p.tile] || above.is_some_and(|b| b != p.top) {
+            return false;
+        }
+
+        let last = self.size - 1;
+        let id = |i: usize| self.tiles[self.placements[self.placed[i]].tile].id;
+        let new_id = self.tiles[p.tile].id;
 
-            new_image[r][c] = sample;
+        // Only keep the solutions where the corner with the smallest ID is at
+        // the top left, and its neighbour to the right has a smaller ID than
+        // its neighbour below. That rules out the other 7 orientations of the
+        // whole image.
+        if last == 0 {
+            return !p.flipped && p.rotation == 0;
         }
+
+        let corner = (x == 0 || x == last) && (y == 0 || y == last);
+
+        (!corner || (x, y) == (0, 0) || new_id > id(0)) && ((x, y) != (0, 1) || new_id > id(1))
     }
+}
 
-    new_image
+/// Reverse the order of the lowest `len` bits.
+fn reverse_bits(n: BorderId, len: usize) -> BorderId {
+    n.reverse_bits() >> (BorderId::BITS as usize - len)
 }
 
-type BorderId = u32;
-type TileId = u64;
+pub type BorderId = u32;
+pub type TileId = u64;
 
 #[derive(Debug, Clone)]
-struct Tile {
-    id: TileId,
-    borders: [BorderId; 4],
-    image: Vec<Vec<bool>>,
+pub struct Tile {
+    pub id: TileId,
+    /// The borders as numbers that are the same whichever way round they are
+    /// read, so that a border matches the same border turned over.
+    pub borders: [BorderId; 4],
+    /// The borders read clockwise, starting from the top left.
+    pub sides: [BorderId; 4],
+    /// The tile without its borders.
+    pub image: Grid<bool>,
 }
 
 impl FromStr for Tile {
-    type Err = Error;
+    type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Tile, Error> {
+    fn from_str(s: &str) -> Result<Tile, ParseError> {
+        let lines: Vec<Line> = parse::lines(s).filter(|l| !l.text.is_empty()).collect();
+        Tile::from_lines(&lines)
+    }
+}
+
+impl Tile {
+    /// Parse a tile from its header line and the rows of squares under it.
+    pub fn from_lines(lines: &[Line]) -> Result<Tile, ParseError> {
         lazy_static! {
-            static ref RE: Regex = Regex::new(r"Tile (\d+):").unwrap();
+            static ref RE: Regex = Regex::new(r"^Tile (\d+):$").unwrap();
         }
 
-        let id = RE.captures(s).unwrap().get(1).unwrap().as_str().parse()?;
+        let header = lines[0];
+        let caps = parse::captures(&RE, header.text, "a header like \"Tile 1234:\"")
+            .map_err(|e| e.at_line(header))?;
+        let id = parse::capture_number(header.text, &caps, 1).map_err(|e| e.at_line(header))?;
+
+        let rows = &lines[1..];
+        let size = rows.len();
+
+        if size < 3 || size > BorderId::BITS as usize {
+            return Err(header.error(
+                1,
+                format!(
+                    "tiles must have between 3 and {} rows, this one has {}",
+                    BorderId::BITS,
+                    size
+                ),
+            ));
+        }
 
-        let data: Vec<Vec<char>> = s
-            .trim()
-            .lines()
-            .skip(1)
-            .map(|line| line.chars().collect())
-            .collect();
+        let data = Grid::parse_lines(rows, grid::bool_cell)?;
 
-        let size = data.len();
+        if data.width() != size {
+            return Err(rows[0].error(
+                1,
+                format!("tiles must be square, expected {} squares", size),
+            ));
+        }
 
         let mut borders = [0; 4];
 
-        fn to_bit(c: char) -> BorderId {
-            match c {
-                '#' => 1,
-                '.' => 0,
-                _ => panic!("Invalid square"),
-            }
-        }
-
         for i in 0..size {
-            let top_square = to_bit(data[0][i]);
-            let right_square = to_bit(data[i][size - 1]);
-            let bottom_square = to_bit(data[size - 1][size - 1 - i]);
-            let left_square = to_bit(data[size - 1 - i][0]);
+            let top_square = data[(i, 0)] as BorderId;
+            let right_square = data[(size - 1, i)] as BorderId;
+            let bottom_square = data[(size - 1 - i, size - 1)] as BorderId;
+            let left_square = data[(0, size - 1 - i)] as BorderId;
 
             borders[0] = (borders[0] << 1) | top_square;
             borders[1] = (borders[1] << 1) | right_square;
@@ -321,44 +607,29 @@ impl FromStr for Tile {
             borders[3] = (borders[3] << 1) | left_square;
         }
 
-        let flip_bits = |mut n| {
-            let mut b = 0;
+        let sides = borders;
+        let borders = sides.map(|b| BorderId::min(b, reverse_bits(b, size)));
 
-            for _ in 0..size {
-                b = (b << 1) | (n & 1);
-                n >>= 1;
-            }
-
-            b
-        };
-
-        // The borders are unique even under reflection, so we can normalise
-        // the IDs
-        for i in 0..4 {
-            borders[i] = BorderId::min(borders[i], flip_bits(borders[i]));
-        }
+        let image = Grid::from_fn(size - 2, size - 2, |x, y| data[(x + 1, y + 1)]);
 
-        let mut image = vec![vec![false; size - 2]; size - 2];
-
-        for r in 0..(size - 2) {
-            for c in 0..(size - 2) {
-                image[r][c] = data[r + 1][c + 1] == '#';
-            }
-        }
-
-        Ok(Tile { id, borders, image })
+        Ok(Tile {
+            id,
+            borders,
+            sides,
+            image,
+        })
     }
 }
 
 #[derive(Debug)]
-struct TransformedTile {
-    tile: Tile,
-    flipped: bool,
-    rotation: usize,
+pub struct TransformedTile {
+    pub tile: Tile,
+    pub flipped: bool,
+    pub rotation: usize,
 }
 
 impl TransformedTile {
-    fn from_tile(tile: Tile) -> TransformedTile {
+    pub fn from_tile(tile: Tile) -> TransformedTile {
         TransformedTile {
             tile,
             flipped: false,
@@ -366,29 +637,29 @@ impl TransformedTile {
         }
     }
 
-    fn flip_h(&mut self) {
+    pub fn flip_h(&mut self) {
         self.flipped = !self.flipped;
     }
 
-    fn flip_v(&mut self) {
+    pub fn flip_v(&mut self) {
         self.flip_h();
         self.rotate();
         self.rotate();
     }
 
-    fn rotate(&mut self) {
+    pub fn rotate(&mut self) {
         self.rotation += 1;
     }
 
-    fn has_border(&self, border: BorderId) -> bool {
+    pub fn has_border(&self, border: BorderId) -> bool {
         self.tile.borders.contains(&border)
     }
 
-    fn top_border(&self) -> BorderId {
-        self.tile.borders[(0 + self.rotation) % 4]
+    pub fn top_border(&self) -> BorderId {
+        self.tile.borders[self.rotation % 4]
     }
 
-    fn right_border(&self) -> BorderId {
+    pub fn right_border(&self) -> BorderId {
         if self.flipped {
             self.tile.borders[(3 + self.rotation) % 4]
         } else {
@@ -396,11 +667,11 @@ impl TransformedTile {
         }
     }
 
-    fn bottom_border(&self) -> BorderId {
+    pub fn bottom_border(&self) -> BorderId {
         self.tile.borders[(2 + self.rotation) % 4]
     }
 
-    fn left_border(&self) -> BorderId {
+    pub fn left_border(&self) -> BorderId {
         if self.flipped {
             self.tile.borders[(1 + self.rotation) % 4]
         } else {
@@ -408,19 +679,21 @@ impl TransformedTile {
         }
     }
 
-    fn sample(&self, r: usize, c: usize) -> bool {
-        let (mut r, mut c) = (r, c);
+    /// The pixel at `(x, y)` of the transformed tile's image.
+    pub fn sample(&self, x: usize, y: usize) -> bool {
+        let last = self.tile.image.width() - 1;
+        let (mut x, mut y) = (x, y);
 
         if self.flipped {
-            c = 7 - c;
+            x = last - x;
         }
 
         for _ in 0..self.rotation {
-            std::mem::swap(&mut r, &mut c);
-            c = 7 - c;
+            std::mem::swap(&mut x, &mut y);
+            x = last - x;
         }
 
-        self.tile.image[r][c]
+        self.tile.image[(x, y)]
     }
 }
 
@@ -531,3 +804,126 @@ fn test_transforms() {
     assert_eq!(tt.bottom_border(), 40);
     assert_eq!(tt.left_border(), 45);
 }
+
+#[test]
+fn tile_counts() {
+    assert_eq!(grid_size(9), Some(3));
+    assert_eq!(grid_size(144), Some(12));
+    assert_eq!(grid_size(8), None);
+
+    let tile = "Tile 1:\n#..\n...\n..#\n\n";
+    let e = Day20.parse(&tile.repeat(2)).unwrap_err();
+    assert_eq!(e.line, 6);
+
+    let big = "Tile 2:\n#...\n....\n....\n...#\n";
+    let e = Day20.parse(&(tile.repeat(3) + big)).unwrap_err();
+    assert_eq!(e.line, 16);
+}
+
+#[test]
+fn assembly_errors() {
+    let blank = |id| format!("Tile {}:\n...\n...\n...\n\n", id);
+    let full = "Tile 4:\n###\n###\n###\n";
+
+    let tiles = Day20
+        .parse(&(1..=4).map(blank).collect::<String>())
+        .unwrap();
+    let e = assemble(&tiles).unwrap_err();
+    assert!(e.to_string().contains("more than one way"));
+    let e = Day20
+        .run(&tiles, Part::Two, &Settings::default())
+        .unwrap_err();
+    assert!(e.to_string().contains("more than one way"));
+
+    let tiles = Day20
+        .parse(&((1..=3).map(blank).collect::<String>() + full))
+        .unwrap();
+    let e = assemble(&tiles).unwrap_err();
+    assert!(e.to_string().contains("cannot be fitted"));
+
+    let tiles = Day20.parse(&blank(1)).unwrap();
+    let grid = assemble(&tiles).unwrap();
+    assert_eq!((grid[(0, 0)].flipped, grid[(0, 0)].rotation), (false, 0));
+}
+
+#[test]
+fn pattern_search() {
+    let pattern: Pattern = "##".parse().unwrap();
+    let image = grid::parse_bool("###\n..#\n#..").unwrap();
+    let matches = pattern.find(&image);
+    let at = |m: &Match| (m.x, m.y, m.rotation, m.flipped);
+
+    // Turning "##" over or round twice looks the same, so those are skipped
+    assert_eq!(
+        matches.iter().map(at).collect::<Vec<_>>(),
+        vec![(0, 0, 0, false), (1, 0, 0, false), (2, 0, 1, false)]
+    );
+    assert_eq!(pattern.squares(&matches[2]), vec![(2, 0), (2, 1)]);
+    assert_eq!(roughness(&image, &pattern), 1);
+
+    let corner: Pattern = "#\n##".parse().unwrap();
+    let matches = corner.find(&image);
+    assert_eq!(
+        matches.iter().map(at).collect::<Vec<_>>(),
+        vec![(1, 0, 2, false)]
+    );
+
+    assert!("".parse::<Pattern>().is_err());
+    assert_eq!("# \u{2603}".parse::<Pattern>().unwrap_err().column, 3);
+}
+
+#[test]
+fn export_image() {
+    let tiles = Day20.parse("Tile 7:\n....\n.#..\n....\n....\n").unwrap();
+    let grid = assemble(&tiles).unwrap();
+    let image = stitch(&grid);
+    let highlighted = [(0, 0)].into_iter().collect();
+
+    assert_eq!(render(&image, &HashSet::new()), "#.\n..\n");
+    assert_eq!(render(&image, &highlighted), "O.\n..\n");
+    assert_eq!(to_pbm(&image), "P1\n2 2\n1 0\n0 0\n");
+    assert_eq!(to_pgm(&image, &highlighted), "P2\n2 2\n2\n2 0\n0 0\n");
+    assert_eq!(placements(&grid), "7:0\n");
+
+    let wide = Grid::new(40, 1, true);
+    assert_eq!(to_pbm(&wide).lines().count(), 4);
+}
+
+#[test]
+fn shared_borders() {
+    // Cut a 3x3 puzzle of 6x6 tiles out of a made up image, with the seams
+    // between tiles repeated in both of them
+    let mut seed = 12345u32;
+    let mut image = Grid::from_fn(16, 16, |_, _| {
+        seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+        seed & 0x10000 != 0
+    });
+
+    // Copy the seam between the middle tile and the one to its right to the
+    // top of the top left corner, so that three tiles share that border and
+    // the corner no longer looks like a corner
+    for y in 0..6 {
+        image[(y, 0)] = image[(10, 5 + y)];
+    }
+
+    let mut input = String::new();
+
+    for i in 0..9 {
+        let (tx, ty) = (5 * (i % 3), 5 * (i / 3));
+        input += &format!("Tile {}:\n", 11 * (i + 1));
+
+        for y in 0..6 {
+            input.extend((0..6).map(|x| if image[(tx + x, ty + y)] { '#' } else { '.' }));
+            input.push('\n');
+        }
+
+        input.push('\n');
+    }
+
+    let tiles = Day20.parse(&input).unwrap();
+    let border_to_tile_map = border_to_tile_map(&tiles);
+    assert!(border_to_tile_map.values().any(|ids| ids.len() > 2));
+    let positions = classify(&tiles, &border_to_tile_map);
+    assert_ne!(positions[&11], Position::Corner);
+    assert_eq!(part1(&tiles).unwrap(), 11 * 33 * 77 * 99);
+}
@@ -1,22 +1,34 @@
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution};
 use regex::Regex;
 use std::collections::{HashMap, HashSet};
 
-pub fn solve() {
-    let input = include_str!("../input/day21");
-    let foods = parse(input);
+pub struct Day21;
 
-    let ingredient_to_allergen = map_ingredient_to_allergen(&foods);
+impl Solution for Day21 {
+    const DAY: u32 = 21;
 
-    println!("Part 1: {}", part1(&foods, &ingredient_to_allergen));
-    println!("Part 2: {}", part2(&ingredient_to_allergen));
+    type Input = Vec<Food>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Food>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(&self, input: &Vec<Food>) -> Answer {
+        part1(input, &map_ingredient_to_allergen(input)).into()
+    }
+
+    fn part2(&self, input: &Vec<Food>) -> Answer {
+        part2(&map_ingredient_to_allergen(input)).into()
+    }
 }
 
-fn part1(foods: &[Food], ingredient_to_allergen: &HashMap<&str, &str>) -> u32 {
+pub fn part1(foods: &[Food], ingredient_to_allergen: &HashMap<&str, &str>) -> u32 {
     let mut count = 0;
 
     for f in foods {
         for ingredient in &f.ingredients {
-            if !ingredient_to_allergen.contains_key(ingredient) {
+            if !ingredient_to_allergen.contains_key(ingredient.as_str()) {
                 count += 1;
             }
         }
@@ -25,23 +37,23 @@ fn part1(foods: &[Food], ingredient_to_allergen: &HashMap<&str, &str>) -> u32 {
     count
 }
 
-fn part2(ingredient_to_allergen: &HashMap<&str, &str>) -> String {
+pub fn part2(ingredient_to_allergen: &HashMap<&str, &str>) -> String {
     let mut dangerous_list: Vec<&str> = ingredient_to_allergen.keys().copied().collect();
     dangerous_list.sort_by_key(|k| ingredient_to_allergen[k]);
     dangerous_list.join(",")
 }
 
-fn map_ingredient_to_allergen<'a>(foods: &[Food<'a>]) -> HashMap<&'a str, &'a str> {
+pub fn map_ingredient_to_allergen(foods: &[Food]) -> HashMap<&str, &str> {
     let mut remaining_ingredients = HashSet::new();
     let mut remaining_allergens = HashSet::new();
 
     for f in foods {
-        for &ingredient in &f.ingredients {
-            remaining_ingredients.insert(ingredient);
+        for ingredient in &f.ingredients {
+            remaining_ingredients.insert(ingredient.as_str());
         }
 
-        for &allergen in &f.allergens {
-            remaining_allergens.insert(allergen);
+        for allergen in &f.allergens {
+            remaining_allergens.insert(allergen.as_str());
         }
     }
 
@@ -52,8 +64,8 @@ fn map_ingredient_to_allergen<'a>(foods: &[Food<'a>]) -> HashMap<&'a str, &'a st
             let mut possible: HashSet<&str> = remaining_ingredients.clone();
 
             for f in foods {
-                if f.allergens.contains(&allergen) {
-                    possible = possible.intersection(&f.ingredients).copied().collect();
+                if f.allergens.contains(allergen) {
+                    possible.retain(|&i| f.ingredients.contains(i));
                 }
             }
 
@@ -70,27 +82,44 @@ fn map_ingredient_to_allergen<'a>(foods: &[Food<'a>]) -> HashMap<&'a str, &'a st
     ingredient_to_allergen
 }
 
-fn parse(input: &str) -> Vec<Food> {
+pub fn parse(input: &str) -> Result<Vec<Food>, ParseError> {
     let re = Regex::new(r"^([\w ]+) \(contains ([\w, ]+)\)$").unwrap();
 
     let mut foods = Vec::new();
 
-    for line in input.lines() {
-        let caps = re.captures(line).expect("Invalid line");
-
-        let ingredients = caps.get(1).unwrap().as_str().split(' ').collect();
-        let allergens = caps.get(2).unwrap().as_str().split(", ").collect();
+    for line in parse::lines(input) {
+        let caps = parse::captures(
+            &re,
+            line.text,
+            "a food like \"abc def (contains dairy, fish)\"",
+        )
+        .map_err(|e| e.at_line(line))?;
+
+        let ingredients = caps
+            .get(1)
+            .unwrap()
+            .as_str()
+            .split(' ')
+            .map(String::from)
+            .collect();
+        let allergens = caps
+            .get(2)
+            .unwrap()
+            .as_str()
+            .split(", ")
+            .map(String::from)
+            .collect();
         foods.push(Food {
             ingredients,
             allergens,
         });
     }
 
-    foods
+    Ok(foods)
 }
 
 #[derive(Clone)]
-struct Food<'a> {
-    ingredients: HashSet<&'a str>,
-    allergens: HashSet<&'a str>,
+pub struct Food {
+    pub ingredients: HashSet<String>,
+    pub allergens: HashSet<String>,
 }
@@ -1,30 +1,42 @@
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution};
 use std::collections::{HashSet, VecDeque};
 
-pub fn solve() {
-    let input = include_str!("../input/day22");
-    let mut input_parts = input.split("\n\n");
-    let player_1_input = input_parts.next().unwrap();
-    let player_2_input = input_parts.next().unwrap();
-
-    let player_1: Vec<u32> = player_1_input
-        .trim()
-        .lines()
-        .skip(1)
-        .map(|l| l.parse().unwrap())
-        .collect();
-
-    let player_2: Vec<u32> = player_2_input
-        .trim()
-        .lines()
-        .skip(1)
-        .map(|l| l.parse().unwrap())
-        .collect();
-
-    println!("Part 1: {}", part1(&player_1, &player_2));
-    println!("Part 2: {}", part2(&player_1, &player_2));
+pub struct Day22;
+
+impl Solution for Day22 {
+    const DAY: u32 = 22;
+
+    type Input = (Vec<u32>, Vec<u32>);
+
+    fn parse(&self, input: &str) -> Result<(Vec<u32>, Vec<u32>), ParseError> {
+        let sections = parse::sections(input);
+        let player = |n: usize| -> Result<Vec<u32>, ParseError> {
+            let deck = sections
+                .get(n - 1)
+                .ok_or_else(|| parse::missing(input, &format!("a deck for player {}", n)))?;
+
+            let header = format!("Player {}:", n);
+            if deck[0].text != header {
+                return Err(deck[0].error(1, format!("expected \"{}\"", header)));
+            }
+
+            deck[1..].iter().map(|l| l.parse_number()).collect()
+        };
+
+        Ok((player(1)?, player(2)?))
+    }
+
+    fn part1(&self, input: &(Vec<u32>, Vec<u32>)) -> Answer {
+        part1(&input.0, &input.1).into()
+    }
+
+    fn part2(&self, input: &(Vec<u32>, Vec<u32>)) -> Answer {
+        part2(&input.0, &input.1).into()
+    }
 }
 
-fn part1(player_1: &[u32], player_2: &[u32]) -> u32 {
+pub fn part1(player_1: &[u32], player_2: &[u32]) -> u32 {
     let mut player_1: VecDeque<u32> = player_1.iter().cloned().collect();
     let mut player_2: VecDeque<u32> = player_2.iter().cloned().collect();
 
@@ -57,7 +69,7 @@ fn part1(player_1: &[u32], player_2: &[u32]) -> u32 {
     score
 }
 
-fn part2(player_1: &[u32], player_2: &[u32]) -> u32 {
+pub fn part2(player_1: &[u32], player_2: &[u32]) -> u32 {
     let (_, mut deck) = recursive_combat(player_1.iter().copied(), player_2.iter().copied());
 
     let mut score = 0;
@@ -71,7 +83,7 @@ fn part2(player_1: &[u32], player_2: &[u32]) -> u32 {
     score
 }
 
-fn recursive_combat(
+pub fn recursive_combat(
     player_1: impl Iterator<Item = u32>,
     player_2: impl Iterator<Item = u32>,
 ) -> (Player, VecDeque<u32>) {
@@ -130,7 +142,7 @@ fn recursive_combat(
 }
 
 #[derive(Debug, Clone, Copy)]
-enum Player {
+pub enum Player {
     Player1,
     Player2,
 }
@@ -1,34 +1,74 @@
-pub fn solve() {
-    let input: Vec<u32> = include_str!("../input/day23")
-        .trim()
-        .chars()
-        .map(|c| c.to_digit(10).expect("Expected a digit"))
-        .collect();
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution};
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    const DAY: u32 = 23;
+
+    type Input = Vec<u32>;
+
+    fn parse(&self, input: &str) -> Result<Vec<u32>, ParseError> {
+        let line = parse::lines(input)
+            .next()
+            .ok_or_else(|| parse::missing(input, "the cups"))?;
+        let mut cups = Vec::new();
+
+        for (i, c) in line.text.trim_end().chars().enumerate() {
+            let cup = c
+                .to_digit(10)
+                .ok_or_else(|| line.error(i + 1, format!("expected a digit, found '{}'", c)))?;
+
+            if cup == 0 || cup as usize > line.text.trim_end().len() || cups.contains(&cup) {
+                return Err(
+                    line.error(i + 1, "the cups must be labelled 1 to N, each exactly once")
+                );
+            }
+
+            cups.push(cup);
+        }
+
+        // A move needs the current cup, three cups to pick up and a cup to
+        // put them after
+        if cups.len() < 5 {
+            return Err(line.error(
+                cups.len() + 1,
+                format!("expected at least 5 cups, found {}", cups.len()),
+            ));
+        }
+
+        Ok(cups)
+    }
+
+    fn part1(&self, input: &Vec<u32>) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(&self, input: &Vec<u32>) -> Answer {
+        part2(input).into()
+    }
 }
 
-fn part1(input: &[u32]) -> u32 {
+pub fn part1(input: &[u32]) -> u32 {
     let mut ring = Ring::new(input, input.len() as u32);
     ring.play(100);
     ring.part1_result()
 }
 
-fn part2(input: &[u32]) -> u64 {
+pub fn part2(input: &[u32]) -> u64 {
     let mut ring = Ring::new(input, 1_000_000);
     ring.play(10_000_000);
     ring.part2_result()
 }
 
 #[derive(Debug)]
-struct Ring {
+pub struct Ring {
     next: Vec<u32>,
     current: u32,
 }
 
 impl Ring {
-    fn new(cups: &[u32], limit: u32) -> Ring {
+    pub fn new(cups: &[u32], limit: u32) -> Ring {
         let mut ring = Ring {
             current: cups[0],
             next: vec![0; limit as usize + 1],
@@ -43,7 +83,7 @@ impl Ring {
         ring
     }
 
-    fn nth(&self, cup: u32, n: u32) -> u32 {
+    pub fn nth(&self, cup: u32, n: u32) -> u32 {
         if n == 0 {
             cup
         } else {
@@ -61,7 +101,7 @@ impl Ring {
         }
     }
 
-    fn next(&self, cup: u32) -> u32 {
+    pub fn next(&self, cup: u32) -> u32 {
         self.next[cup as usize]
     }
 
@@ -69,13 +109,13 @@ impl Ring {
         self.next[cup as usize] = next;
     }
 
-    fn play(&mut self, rounds: u32) {
+    pub fn play(&mut self, rounds: u32) {
         for _ in 0..rounds {
             self.play_round();
         }
     }
 
-    fn play_round(&mut self) {
+    pub fn play_round(&mut self) {
         // Remove a segment of three cups
         let segment = self.next(self.current);
         self.set_next(self.current, self.nth(self.current, 4));
@@ -101,7 +141,7 @@ impl Ring {
         self.current = self.next(self.current);
     }
 
-    fn part1_result(&self) -> u32 {
+    pub fn part1_result(&self) -> u32 {
         let mut result = 0;
         let mut n = self.next(1);
 
@@ -113,9 +153,17 @@ impl Ring {
         result
     }
 
-    fn part2_result(&self) -> u64 {
+    pub fn part2_result(&self) -> u64 {
         let a = self.next(1);
         let b = self.next(a);
         a as u64 * b as u64
     }
 }
+
+#[test]
+fn too_few_cups() {
+    assert_eq!(Day23.parse("1\n").unwrap_err().column, 2);
+    assert_eq!(Day23.parse("\n").unwrap_err().column, 1);
+    assert_eq!(Day23.parse("2143\n").unwrap_err().column, 5);
+    assert!(Day23.parse("21435\n").is_ok());
+}
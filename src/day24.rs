@@ -1,98 +1,90 @@
-use lazy_static::lazy_static;
-use regex::Regex;
-use std::collections::{HashMap, HashSet};
+use crate::automaton::{Automaton, CellSet, HexGrid, Rule};
+use crate::hex::{self, Hex, Path};
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution};
+use std::collections::HashSet;
 
-pub fn solve() {
-    let input: Vec<Vec<_>> = include_str!("../input/day24").lines().map(parse).collect();
+pub struct Day24;
 
-    let black = create_initial_state(&input);
+impl Solution for Day24 {
+    const DAY: u32 = 24;
 
-    println!("Part 1: {}", part1(&black));
-    println!("Part 2: {}", part2(&black));
+    type Input = HashSet<Hex>;
+
+    fn parse(&self, input: &str) -> Result<HashSet<Hex>, ParseError> {
+        let input: Vec<Path> = parse::lines(input)
+            .map(|l| l.parse())
+            .collect::<Result<_, _>>()?;
+        Ok(create_initial_state(&input))
+    }
+
+    fn part1(&self, input: &HashSet<Hex>) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(&self, input: &HashSet<Hex>) -> Answer {
+        part2(input).into()
+    }
 }
 
-fn part1(black: &HashSet<(i32, i32)>) -> usize {
+pub fn part1(black: &HashSet<Hex>) -> usize {
     black.len()
 }
 
-fn part2(black: &HashSet<(i32, i32)>) -> usize {
-    let mut current = black.clone();
-    let mut next = HashSet::new();
-    let mut neighbours = HashMap::new();
-
-    let deltas = [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+pub fn part2(black: &HashSet<Hex>) -> usize {
+    let mut floor = floor(black);
+    floor.run(DAYS);
+    floor.population()
+}
 
-    for _ in 0..100 {
-        for &tile in &current {
-            for &delta in &deltas {
-                let pos = (tile.0 + delta.0, tile.1 + delta.1);
-                *neighbours.entry(pos).or_insert(0) += 1;
-            }
-        }
+/// How many days the floor is flipped for.
+pub const DAYS: usize = 100;
 
-        for (pos, count) in neighbours.drain() {
-            if current.contains(&pos) {
-                // Any black tile with zero or more than 2 black tiles
-                // immediately adjacent to it is flipped to white.
-                if count > 0 && count <= 2 {
-                    next.insert(pos);
-                }
-            } else {
-                // Any white tile with exactly 2 black tiles immediately
-                // adjacent to it is flipped to black.
-                if count == 2 {
-                    next.insert(pos);
-                }
-            }
-        }
+/// Black tiles with zero or more than 2 black neighbours are flipped to white,
+/// and white tiles with exactly 2 black neighbours are flipped to black.
+pub fn floor(black: &HashSet<Hex>) -> Automaton<HexGrid> {
+    Automaton::new(HexGrid, Rule::new(&[2], &[1, 2]), black.iter().copied())
+}
 
-        std::mem::swap(&mut next, &mut current);
-        next.clear();
-    }
+/// The black tile furthest from the reference tile, and how many steps away
+/// it is.
+pub fn furthest<'a>(black: impl IntoIterator<Item = &'a Hex>) -> Option<(Hex, u32)> {
+    black
+        .into_iter()
+        .map(|&t| (t, t.length()))
+        .max_by_key(|&(t, d)| (d, t))
+}
 
-    current.len()
+/// Draw the floor with north at the top, `#` for black tiles and `.` for white
+/// ones. Each row of tiles is offset by half a tile from the one above it.
+pub fn render(black: &CellSet<Hex>) -> String {
+    hex::render(black.iter().copied(), |t| {
+        if black.contains(&t) {
+            '#'
+        } else {
+            '.'
+        }
+    })
 }
 
-fn create_initial_state(input: &[Vec<(i32, i32)>]) -> HashSet<(i32, i32)> {
+pub fn create_initial_state(input: &[Path]) -> HashSet<Hex> {
     let mut black = HashSet::new();
 
-    for steps in input {
-        let mut pos = (0, 0);
+    for path in input {
+        let tile = path.end(Hex::ORIGIN);
 
-        for step in steps {
-            pos = (pos.0 + step.0, pos.1 + step.1);
-        }
-
-        if black.contains(&pos) {
-            black.remove(&pos);
-        } else {
-            black.insert(pos);
+        if !black.remove(&tile) {
+            black.insert(tile);
         }
     }
 
     black
 }
 
-fn parse(s: &str) -> Vec<(i32, i32)> {
-    lazy_static! {
-        static ref RE: Regex = Regex::new("e|se|sw|w|nw|ne").unwrap();
-    }
-
-    let mut steps = Vec::new();
-
-    for m in RE.find_iter(s) {
-        let step = match m.as_str() {
-            "e" => (1, 0),
-            "se" => (1, -1),
-            "sw" => (0, -1),
-            "w" => (-1, 0),
-            "nw" => (-1, 1),
-            "ne" => (0, 1),
-            _ => panic!("Invalid direction"),
-        };
-
-        steps.push(step);
-    }
-
-    steps
+#[test]
+fn furthest_tile() {
+    let black = Day24.parse("esew\nnwwswee\nnenenene\n").unwrap();
+    assert_eq!(black.len(), 3);
+    assert_eq!(furthest(&black), Some((Hex::new(0, 4), 4)));
+    assert_eq!(furthest(&HashSet::new()), None);
 }
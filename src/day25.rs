@@ -1,17 +1,35 @@
-pub fn solve() {
-    let input: Vec<u64> = include_str!("../input/day25")
-        .lines()
-        .map(|l| l.parse().unwrap())
-        .collect();
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution};
 
-    println!("Part 1: {}", part1(&input));
+pub struct Day25;
+
+impl Solution for Day25 {
+    const DAY: u32 = 25;
+
+    type Input = Vec<u64>;
+
+    fn parse(&self, input: &str) -> Result<Vec<u64>, ParseError> {
+        let keys: Vec<u64> = parse::lines(input)
+            .map(|l| l.parse_number())
+            .collect::<Result<_, _>>()?;
+
+        if keys.len() < 2 {
+            return Err(parse::missing(input, "two public keys"));
+        }
+
+        Ok(keys)
+    }
+
+    fn part1(&self, input: &Vec<u64>) -> Answer {
+        part1(input).into()
+    }
 }
 
-fn part1(input: &[u64]) -> u64 {
+pub fn part1(input: &[u64]) -> u64 {
     transform(input[0], crack(input[1]))
 }
 
-fn crack(public_key: u64) -> u64 {
+pub fn crack(public_key: u64) -> u64 {
     let mut value = 1;
     let mut private_key = 0;
     let subject = 7;
@@ -25,7 +43,7 @@ fn crack(public_key: u64) -> u64 {
     private_key
 }
 
-fn transform(subject: u64, key: u64) -> u64 {
+pub fn transform(subject: u64, key: u64) -> u64 {
     let mut value = 1;
 
     for _ in 0..key {
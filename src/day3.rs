@@ -1,60 +1,45 @@
-use std::collections::hash_set::HashSet;
-use std::str::FromStr;
+use crate::grid::{self, Grid};
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
 
-struct Map {
-    width: usize,
-    height: usize,
+/// Trees are `true`.
+pub type Map = Grid<bool>;
 
-    // (x, y) coordinates of trees in the map
-    trees: HashSet<(usize, usize)>,
-}
-
-impl FromStr for Map {
-    type Err = ();
+pub struct Day3;
 
-    fn from_str(s: &str) -> Result<Map, ()> {
-        let width = s.lines().next().unwrap().len();
-        let height = s.lines().count();
-        let mut trees = HashSet::new();
+impl Solution for Day3 {
+    const DAY: u32 = 3;
 
-        for (y, line) in s.lines().enumerate() {
-            for (x, c) in line.chars().enumerate() {
-                if c == '#' {
-                    trees.insert((x, y));
-                }
-            }
-        }
+    type Input = Map;
 
-        Ok(Map {
-            width,
-            height,
-            trees,
-        })
+    fn parse(&self, input: &str) -> Result<Map, ParseError> {
+        grid::parse_bool(input)
     }
-}
 
-pub fn solve() {
-    let map = include_str!("../input/day3").parse().unwrap();
+    fn part1(&self, input: &Map) -> Answer {
+        part1(input).into()
+    }
 
-    println!("Part 1: {}", part1(&map));
-    println!("Part 2: {}", part2(&map));
+    fn part2(&self, input: &Map) -> Answer {
+        part2(input).into()
+    }
 }
 
-fn part1(map: &Map) -> u64 {
+pub fn part1(map: &Map) -> u64 {
     walk(map, 3, 1)
 }
 
-fn part2(map: &Map) -> u64 {
+pub fn part2(map: &Map) -> u64 {
     walk(map, 1, 1) * walk(map, 3, 1) * walk(map, 5, 1) * walk(map, 7, 1) * walk(map, 1, 2)
 }
 
-fn walk(map: &Map, across: usize, down: usize) -> u64 {
+pub fn walk(map: &Map, across: usize, down: usize) -> u64 {
     let mut trees = 0;
 
-    for y in (0..map.height).step_by(down) {
-        let x = (y * across / down) % map.width;
+    for y in (0..map.height()).step_by(down) {
+        let x = (y * across / down) % map.width();
 
-        if map.trees.contains(&(x, y)) {
+        if map[(x, y)] {
             trees += 1;
         }
     }
@@ -1,27 +1,42 @@
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution};
 use regex::Regex;
 use std::collections::HashMap;
 
-type Passport = HashMap<String, String>;
+pub type Passport = HashMap<String, String>;
 
-pub fn solve() {
-    let passports = parse_passports(include_str!("../input/day4"));
+pub struct Day4;
 
-    println!("Part 1: {}", part1(&passports));
-    println!("Part 2: {}", part2(&passports));
+impl Solution for Day4 {
+    const DAY: u32 = 4;
+
+    type Input = Vec<Passport>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Passport>, ParseError> {
+        parse_passports(input)
+    }
+
+    fn part1(&self, input: &Vec<Passport>) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(&self, input: &Vec<Passport>) -> Answer {
+        part2(input).into()
+    }
 }
 
-fn part1(passports: &[Passport]) -> usize {
+pub fn part1(passports: &[Passport]) -> usize {
     passports
         .iter()
         .filter(|p| passport_valid(p, false))
         .count()
 }
 
-fn part2(passports: &[Passport]) -> usize {
+pub fn part2(passports: &[Passport]) -> usize {
     passports.iter().filter(|p| passport_valid(p, true)).count()
 }
 
-fn passport_valid(p: &Passport, check_values: bool) -> bool {
+pub fn passport_valid(p: &Passport, check_values: bool) -> bool {
     let rules: HashMap<_, _> = [
         ("byr", Regex::new(r"\d+").unwrap()),
         ("iyr", Regex::new(r"\d+").unwrap()),
@@ -47,17 +62,17 @@ fn passport_valid(p: &Passport, check_values: bool) -> bool {
         }
 
         let byr: u32 = p["byr"].parse().unwrap();
-        if byr < 1920 || byr > 2002 {
+        if !(1920..=2002).contains(&byr) {
             return false;
         }
 
         let iyr: u32 = p["iyr"].parse().unwrap();
-        if iyr < 2010 || iyr > 2020 {
+        if !(2010..=2020).contains(&iyr) {
             return false;
         }
 
         let eyr: u32 = p["eyr"].parse().unwrap();
-        if eyr < 2020 || eyr > 2030 {
+        if !(2020..=2030).contains(&eyr) {
             return false;
         }
 
@@ -67,12 +82,12 @@ fn passport_valid(p: &Passport, check_values: bool) -> bool {
 
         match hgt_unit {
             "in" => {
-                if hgt < 59 || hgt > 76 {
+                if !(59..=76).contains(&hgt) {
                     return false;
                 }
             }
             "cm" => {
-                if hgt < 150 || hgt > 193 {
+                if !(150..=193).contains(&hgt) {
                     return false;
                 }
             }
@@ -83,28 +98,25 @@ fn passport_valid(p: &Passport, check_values: bool) -> bool {
     true
 }
 
-pub fn parse_passports(input: &str) -> Vec<Passport> {
+pub fn parse_passports(input: &str) -> Result<Vec<Passport>, ParseError> {
     let mut passports = Vec::new();
-    let mut p = HashMap::new();
-
-    let re = Regex::new(r"(\w+):([^ ]+)").unwrap();
-
-    for line in input.lines() {
-        if line.trim().is_empty() {
-            passports.push(p);
-            p = HashMap::new();
-        } else {
-            for cap in re.captures_iter(line) {
-                let key = cap.get(1).unwrap().as_str().to_string();
-                let value = cap.get(2).unwrap().as_str().to_string();
-                p.insert(key, value);
+
+    for section in parse::sections(input) {
+        let mut p = HashMap::new();
+
+        for line in section {
+            for field in line.text.split_whitespace() {
+                match field.split_once(':') {
+                    Some((key, value)) if !key.is_empty() && !value.is_empty() => {
+                        p.insert(key.to_string(), value.to_string());
+                    }
+                    _ => return Err(line.error_at(field, "expected a field like \"key:value\"")),
+                }
             }
         }
-    }
 
-    if !p.is_empty() {
         passports.push(p);
     }
 
-    passports
+    Ok(passports)
 }
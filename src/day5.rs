@@ -1,20 +1,40 @@
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution};
 use std::collections::hash_set::HashSet;
 
-pub fn solve() {
-    let input: HashSet<u32> = include_str!("../input/day5")
-        .lines()
-        .map(seat_id_from_pass)
-        .collect();
+pub struct Day5;
 
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+impl Solution for Day5 {
+    const DAY: u32 = 5;
+
+    type Input = HashSet<u32>;
+
+    fn parse(&self, input: &str) -> Result<HashSet<u32>, ParseError> {
+        let seat_ids: HashSet<u32> = parse::lines(input)
+            .map(|l| seat_id_from_pass(l.text).map_err(|e| e.at_line(l)))
+            .collect::<Result<_, _>>()?;
+
+        if seat_ids.is_empty() {
+            return Err(parse::missing(input, "a boarding pass"));
+        }
+
+        Ok(seat_ids)
+    }
+
+    fn part1(&self, input: &HashSet<u32>) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(&self, input: &HashSet<u32>) -> Answer {
+        part2(input).into()
+    }
 }
 
-fn part1(seat_ids: &HashSet<u32>) -> u32 {
+pub fn part1(seat_ids: &HashSet<u32>) -> u32 {
     *seat_ids.iter().max().unwrap()
 }
 
-fn part2(seat_ids: &HashSet<u32>) -> u32 {
+pub fn part2(seat_ids: &HashSet<u32>) -> u32 {
     let first_seat = *seat_ids.iter().min().unwrap();
     let last_seat = *seat_ids.iter().max().unwrap();
 
@@ -27,20 +47,26 @@ fn part2(seat_ids: &HashSet<u32>) -> u32 {
     panic!("No free seats")
 }
 
-fn seat_id_from_pass(pass: &str) -> u32 {
+pub fn seat_id_from_pass(pass: &str) -> Result<u32, ParseError> {
+    let mut seat_id = 0;
+
     // Binary representation of the seat ID, MSB first
-    let seat_id_bin = pass.chars().map(|c| match c {
-        'B' | 'R' => 1,
-        'F' | 'L' => 0,
-        _ => panic!("Invalid boarding pass"),
-    });
+    for (i, c) in pass.chars().enumerate() {
+        let b = match c {
+            'B' | 'R' => 1,
+            'F' | 'L' => 0,
+            _ => return Err(ParseError::new(i + 1, format!("unexpected '{}'", c))),
+        };
+
+        seat_id = 2 * seat_id + b;
+    }
 
-    seat_id_bin.fold(0, |acc, b| 2 * acc + b)
+    Ok(seat_id)
 }
 
 #[test]
 fn seat_id_from_pass_on_example() {
-    assert_eq!(seat_id_from_pass("BFFFBBFRRR"), 567);
-    assert_eq!(seat_id_from_pass("FFFBBBFRRR"), 119);
-    assert_eq!(seat_id_from_pass("BBFFBBFRLL"), 820);
+    assert_eq!(seat_id_from_pass("BFFFBBFRRR"), Ok(567));
+    assert_eq!(seat_id_from_pass("FFFBBBFRRR"), Ok(119));
+    assert_eq!(seat_id_from_pass("BBFFBBFRLL"), Ok(820));
 }
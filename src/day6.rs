@@ -1,25 +1,44 @@
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution};
 use std::collections::hash_set::HashSet;
 
-pub fn solve() {
-    let input = include_str!("../input/day6");
+pub struct Day6;
 
-    let mut groups = Vec::new();
+impl Solution for Day6 {
+    const DAY: u32 = 6;
 
-    for group_str in input.split("\n\n") {
-        let mut group = Vec::new();
+    type Input = Vec<Vec<HashSet<char>>>;
 
-        for line in group_str.lines() {
-            group.push(line.chars().collect());
+    fn parse(&self, input: &str) -> Result<Vec<Vec<HashSet<char>>>, ParseError> {
+        let mut groups = Vec::new();
+
+        for section in parse::sections(input) {
+            let mut group = Vec::new();
+
+            for line in section {
+                if let Some(i) = line.text.find(|c: char| !c.is_ascii_lowercase()) {
+                    return Err(line.error(i + 1, "expected a question from a to z"));
+                }
+
+                group.push(line.text.chars().collect());
+            }
+
+            groups.push(group);
         }
 
-        groups.push(group);
+        Ok(groups)
     }
 
-    println!("Part 1: {}", part1(&groups));
-    println!("Part 2: {}", part2(&groups));
+    fn part1(&self, input: &Vec<Vec<HashSet<char>>>) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(&self, input: &Vec<Vec<HashSet<char>>>) -> Answer {
+        part2(input).into()
+    }
 }
 
-fn part1(groups: &[Vec<HashSet<char>>]) -> usize {
+pub fn part1(groups: &[Vec<HashSet<char>>]) -> usize {
     let mut count = 0;
 
     for g in groups {
@@ -35,7 +54,7 @@ fn part1(groups: &[Vec<HashSet<char>>]) -> usize {
     count
 }
 
-fn part2(groups: &[Vec<HashSet<char>>]) -> usize {
+pub fn part2(groups: &[Vec<HashSet<char>>]) -> usize {
     let mut count = 0;
 
     for g in groups {
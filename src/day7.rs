@@ -1,16 +1,30 @@
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution};
 use regex::Regex;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
-pub fn solve() {
-    let input = include_str!("../input/day7");
-    let graph = Graph::from_rules(input);
+pub struct Day7;
 
-    println!("Part 1: {}", part1(&graph));
-    println!("Part 2: {}", part2(&graph));
+impl Solution for Day7 {
+    const DAY: u32 = 7;
+
+    type Input = Graph;
+
+    fn parse(&self, input: &str) -> Result<Graph, ParseError> {
+        Graph::from_rules(input)
+    }
+
+    fn part1(&self, input: &Graph) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(&self, input: &Graph) -> Answer {
+        part2(input).into()
+    }
 }
 
-fn part1(graph: &Graph) -> u32 {
+pub fn part1(graph: &Graph) -> u32 {
     // Start with all the bags that directly contain a shiny gold bag
     let mut outer_bags: HashSet<u32> = graph.outer_bags(graph.node("shiny gold")).collect();
 
@@ -31,47 +45,61 @@ fn part1(graph: &Graph) -> u32 {
     outer_bags.len() as u32
 }
 
-fn part2(graph: &Graph) -> u32 {
+pub fn part2(graph: &Graph) -> u32 {
     graph.count_inner_bags(graph.node("shiny gold"))
 }
 
-type NodeID = u32;
+pub type NodeID = u32;
 
 #[derive(Hash, Eq, PartialEq, Debug)]
-struct Edge {
-    outer: NodeID,
-    inner: NodeID,
-    count: u32,
+pub struct Edge {
+    pub outer: NodeID,
+    pub inner: NodeID,
+    pub count: u32,
 }
 
 #[derive(Debug)]
-struct Graph {
+pub struct Graph {
     nodes: HashMap<String, NodeID>,
     edges: Vec<Edge>,
 }
 
 impl Graph {
-    fn from_rules(rules: &str) -> Graph {
+    pub fn from_rules(rules: &str) -> Result<Graph, ParseError> {
         let outer_re = Regex::new(r"^(\w+ \w+) bags contain ([\w ,]+)\.$").unwrap();
-        let inner_re = Regex::new(r"(\d+) (\w+ \w+) bag").unwrap();
+        let inner_re = Regex::new(r"^(\d+) (\w+ \w+) bags?$").unwrap();
 
         let mut graph = Graph {
             nodes: HashMap::new(),
             edges: Vec::new(),
         };
 
-        for line in rules.lines() {
-            let m = outer_re.captures(line).unwrap();
+        for line in parse::lines(rules) {
+            let m = parse::captures(
+                &outer_re,
+                line.text,
+                "a rule like \"<colour> bags contain ...\"",
+            )
+            .map_err(|e| e.at_line(line))?;
             let outer = graph.insert_node(m.get(1).unwrap().as_str());
+            let contents = m.get(2).unwrap().as_str();
+
+            if contents == "no other bags" {
+                continue;
+            }
 
-            for m in inner_re.captures_iter(m.get(2).unwrap().as_str()) {
-                let count = m.get(1).unwrap().as_str().parse().unwrap();
+            for item in contents.split(", ") {
+                let m = inner_re
+                    .captures(item)
+                    .ok_or_else(|| line.error_at(item, "expected \"<count> <colour> bags\""))?;
+                let count = parse::capture_number(item, &m, 1)
+                    .map_err(|_| line.error_at(item, "expected a count of bags"))?;
                 let inner = graph.insert_node(m.get(2).unwrap().as_str());
                 graph.insert_edge(outer, inner, count);
             }
         }
 
-        graph
+        Ok(graph)
     }
 
     fn insert_node(&mut self, node: &str) -> NodeID {
@@ -79,7 +107,7 @@ impl Graph {
         *self.nodes.entry(node.to_string()).or_insert(next_node_id)
     }
 
-    fn node(&self, node: &str) -> NodeID {
+    pub fn node(&self, node: &str) -> NodeID {
         *self.nodes.get(node).unwrap()
     }
 
@@ -91,7 +119,7 @@ impl Graph {
         });
     }
 
-    fn outer_bags(&self, inner: NodeID) -> impl Iterator<Item = NodeID> + '_ {
+    pub fn outer_bags(&self, inner: NodeID) -> impl Iterator<Item = NodeID> + '_ {
         self.edges.iter().filter_map(move |e| {
             if e.inner == inner {
                 Some(e.outer)
@@ -101,7 +129,7 @@ impl Graph {
         })
     }
 
-    fn inner_bags(&self, outer: NodeID) -> impl Iterator<Item = (u32, NodeID)> + '_ {
+    pub fn inner_bags(&self, outer: NodeID) -> impl Iterator<Item = (u32, NodeID)> + '_ {
         self.edges.iter().filter_map(move |e| {
             if e.outer == outer {
                 Some((e.count, e.inner))
@@ -111,7 +139,7 @@ impl Graph {
         })
     }
 
-    fn count_inner_bags(&self, outer_bag: NodeID) -> u32 {
+    pub fn count_inner_bags(&self, outer_bag: NodeID) -> u32 {
         let mut total_count = 0;
 
         for (count, inner_bag) in self.inner_bags(outer_bag) {
@@ -1,22 +1,33 @@
-use anyhow::{anyhow, bail, Error};
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution};
 use std::collections::HashSet;
 use std::str::FromStr;
 
-pub fn solve() {
-    let input: Vec<Instr> = include_str!("../input/day8")
-        .lines()
-        .map(|s| s.parse().unwrap())
-        .collect();
+pub struct Day8;
 
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+impl Solution for Day8 {
+    const DAY: u32 = 8;
+
+    type Input = Vec<Instr>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Instr>, ParseError> {
+        parse::parse_lines(input)
+    }
+
+    fn part1(&self, input: &Vec<Instr>) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(&self, input: &Vec<Instr>) -> Answer {
+        part2(input).into()
+    }
 }
 
-fn part1(program: &[Instr]) -> i32 {
+pub fn part1(program: &[Instr]) -> i32 {
     execute(program).1
 }
 
-fn part2(program: &[Instr]) -> i32 {
+pub fn part2(program: &[Instr]) -> i32 {
     for i in 0..program.len() {
         let instr = program[i];
 
@@ -48,7 +59,7 @@ fn part2(program: &[Instr]) -> i32 {
     panic!("No valid programs found");
 }
 
-fn execute(program: &[Instr]) -> (bool, i32) {
+pub fn execute(program: &[Instr]) -> (bool, i32) {
     let mut pc = 0;
     let mut acc = 0;
     let mut trace = HashSet::new();
@@ -82,28 +93,26 @@ fn execute(program: &[Instr]) -> (bool, i32) {
 }
 
 #[derive(Debug, Copy, Clone)]
-enum Instr {
+pub enum Instr {
     Jmp(i32),
     Acc(i32),
     Nop(i32),
 }
 
 impl FromStr for Instr {
-    type Err = Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split(' ');
-        let opcode = parts.next().ok_or_else(|| anyhow!("Invalid input"))?;
-        let value: i32 = parts
-            .next()
-            .ok_or_else(|| anyhow!("Invalid input"))?
-            .parse()?;
+        let (opcode, value) = s
+            .split_once(' ')
+            .ok_or_else(|| ParseError::new(1, "expected an instruction like \"acc +1\""))?;
+        let value: i32 = parse::number(s, value)?;
 
         let instr = match opcode {
             "jmp" => Instr::Jmp(value),
             "acc" => Instr::Acc(value),
             "nop" => Instr::Nop(value),
-            _ => bail!("Invalid opcode"),
+            _ => return Err(ParseError::new(1, format!("unknown opcode \"{}\"", opcode))),
         };
 
         Ok(instr)
@@ -1,18 +1,31 @@
-pub fn solve() {
-    let input: Vec<i64> = include_str!("../input/day9")
-        .lines()
-        .map(|s| s.parse().unwrap())
-        .collect();
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution};
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u32 = 9;
+
+    type Input = Vec<i64>;
+
+    fn parse(&self, input: &str) -> Result<Vec<i64>, ParseError> {
+        parse::lines(input).map(|l| l.parse_number()).collect()
+    }
+
+    fn part1(&self, input: &Vec<i64>) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(&self, input: &Vec<i64>) -> Answer {
+        part2(input).into()
+    }
 }
 
-fn part1(input: &[i64]) -> i64 {
+pub fn part1(input: &[i64]) -> i64 {
     find_first_invalid(input)
 }
 
-fn part2(input: &[i64]) -> i64 {
+pub fn part2(input: &[i64]) -> i64 {
     let num = find_first_invalid(input);
 
     for start in 0..input.len() {
@@ -34,7 +47,7 @@ fn part2(input: &[i64]) -> i64 {
     panic!("No weakness found");
 }
 
-fn find_first_invalid(input: &[i64]) -> i64 {
+pub fn find_first_invalid(input: &[i64]) -> i64 {
     let mut window = [0; 25];
 
     window[..25].copy_from_slice(&input[..25]);
@@ -52,7 +65,7 @@ fn find_first_invalid(input: &[i64]) -> i64 {
     panic!("No invalid number found")
 }
 
-fn is_valid(num: i64, window: &[i64]) -> bool {
+pub fn is_valid(num: i64, window: &[i64]) -> bool {
     for &x in window {
         let y = num - x;
 
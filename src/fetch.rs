@@ -0,0 +1,224 @@
+use anyhow::{anyhow, bail, Context, Error};
+use std::env;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2020";
+
+const USER_AGENT: &str = "github.com/danielcannell/advent-of-code-2020";
+
+/// Read the session cookie from the `AOC_SESSION` environment variable, or
+/// failing that from a file called `AOC_SESSION`.
+pub fn session() -> Result<String, Error> {
+    let session = match env::var("AOC_SESSION") {
+        Ok(session) => session,
+        Err(_) => fs::read_to_string("AOC_SESSION")
+            .context("No session cookie: set AOC_SESSION or put it in a file called AOC_SESSION")?,
+    };
+
+    let session = session.trim();
+
+    if session.is_empty() {
+        bail!("The session cookie is empty");
+    }
+
+    Ok(session.to_string())
+}
+
+/// A client for the Advent of Code website, or anything that looks like it.
+pub struct Client {
+    base_url: String,
+    session: String,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str) -> Client {
+        Client {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+            agent: ureq::AgentBuilder::new().user_agent(USER_AGENT).build(),
+        }
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    fn cookie(&self) -> String {
+        format!("session={}", self.session)
+    }
+
+    /// Download the puzzle input for a day.
+    pub fn input(&self, day: u32) -> Result<String, Error> {
+        let url = self.url(&format!("/day/{}/input", day));
+        let response = self
+            .agent
+            .get(&url)
+            .set("Cookie", &self.cookie())
+            .call()
+            .map_err(|e| request_error(&url, e))?;
+
+        let input = read_body(response)?;
+
+        if input.trim().is_empty() {
+            bail!("{} returned an empty input", url);
+        }
+
+        Ok(input)
+    }
+
+    /// POST a form and return the body of the response.
+    pub fn post_form(&self, path: &str, form: &[(&str, &str)]) -> Result<String, Error> {
+        let url = self.url(path);
+        let response = self
+            .agent
+            .post(&url)
+            .set("Cookie", &self.cookie())
+            .send_form(form)
+            .map_err(|e| request_error(&url, e))?;
+
+        read_body(response)
+    }
+}
+
+fn request_error(url: &str, e: ureq::Error) -> Error {
+    match e {
+        ureq::Error::Status(status, response) => {
+            let body = response.into_string().unwrap_or_default();
+
+            // Plain text error messages are worth showing, whole error pages
+            // are not
+            match body.lines().next().map(str::trim) {
+                Some(reason) if !reason.is_empty() && !reason.starts_with('<') => {
+                    anyhow!("{} returned HTTP {}: {}", url, status, reason)
+                }
+                _ => anyhow!("{} returned HTTP {}", url, status),
+            }
+        }
+        e => anyhow!("{}", e),
+    }
+}
+
+fn read_body(response: ureq::Response) -> Result<String, Error> {
+    let mut body = String::new();
+    response
+        .into_reader()
+        .read_to_string(&mut body)
+        .context("Could not read the response")?;
+    Ok(body)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Fetched {
+    /// The input was already there, so nothing was downloaded.
+    Cached,
+    Downloaded,
+}
+
+/// Download the input for a day to `path`, unless it is already there.
+pub fn fetch(client: &Client, day: u32, path: &Path, force: bool) -> Result<Fetched, Error> {
+    if path.exists() && !force {
+        return Ok(Fetched::Cached);
+    }
+
+    let input = client.input(day)?;
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Could not create {}", dir.display()))?;
+    }
+
+    // Write to a temporary file first so that a failed write never leaves a
+    // partial input behind
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, input).with_context(|| format!("Could not write {}", tmp.display()))?;
+    fs::rename(&tmp, path).with_context(|| format!("Could not write {}", path.display()))?;
+
+    Ok(Fetched::Downloaded)
+}
+
+/// A stand-in for the website that answers one request, for testing.
+#[cfg(test)]
+pub(crate) fn serve_once(
+    status: u32,
+    body: &'static str,
+) -> (String, std::thread::JoinHandle<String>) {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+    let handle = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut request = String::new();
+        let mut content_length = 0;
+
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+
+            if let Some(n) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                content_length = n.trim().parse().unwrap();
+            }
+
+            request += &line;
+
+            if line == "\r\n" {
+                break;
+            }
+        }
+
+        let mut content = vec![0; content_length];
+        reader.read_exact(&mut content).unwrap();
+        request += &String::from_utf8(content).unwrap();
+
+        write!(
+            reader.get_mut(),
+            "HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        )
+        .unwrap();
+
+        request
+    });
+
+    (base_url, handle)
+}
+
+#[test]
+fn fetch_from_stand_in_server() {
+    let dir = env::temp_dir().join(format!("aoc-fetch-test-{}", std::process::id()));
+    let path = dir.join("day1");
+    let _ = fs::remove_dir_all(&dir);
+
+    let (base_url, server) = serve_once(200, "1721\n979\n");
+    let client = Client::new(&base_url, "abc");
+    assert_eq!(
+        fetch(&client, 1, &path, false).unwrap(),
+        Fetched::Downloaded
+    );
+    assert_eq!(fs::read_to_string(&path).unwrap(), "1721\n979\n");
+
+    let request = server.join().unwrap();
+    assert!(request.starts_with("GET /day/1/input "));
+    assert!(request.contains("session=abc"));
+
+    // Already cached, so there is no request
+    assert_eq!(fetch(&client, 1, &path, false).unwrap(), Fetched::Cached);
+
+    // Error pages are not saved
+    let (base_url, _) = serve_once(400, "Puzzle inputs differ by user.");
+    let client = Client::new(&base_url, "abc");
+    let e = fetch(&client, 1, &path, true).unwrap_err();
+    assert!(e
+        .to_string()
+        .contains("HTTP 400: Puzzle inputs differ by user."));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "1721\n979\n");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
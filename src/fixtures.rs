@@ -0,0 +1,150 @@
+use crate::answers::parse_answer;
+use crate::registry;
+use crate::runner;
+use crate::solution::{Part, Settings};
+use anyhow::{anyhow, bail, Context, Error};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A puzzle example and its expected answers, read from a file in
+/// `fixtures/dayN/`. The expected answers come first, then a `---` line, then
+/// the input:
+///
+/// ```text
+/// part1 = 514579
+/// part2 = 241861950
+/// ---
+/// 1721
+/// 979
+/// ```
+///
+/// Either answer can be left out, and then that part is not run.
+#[derive(Debug)]
+pub struct Fixture {
+    pub day: u32,
+    pub path: PathBuf,
+    pub input: String,
+    pub expected: Vec<(Part, String)>,
+}
+
+impl Fixture {
+    pub fn load(day: u32, path: &Path) -> Result<Fixture, Error> {
+        let s = fs::read_to_string(path)
+            .with_context(|| format!("Could not read {}", path.display()))?;
+        Fixture::parse(day, path, &s).with_context(|| format!("Could not parse {}", path.display()))
+    }
+
+    pub fn parse(day: u32, path: &Path, s: &str) -> Result<Fixture, Error> {
+        let (header, input) = match s.split_once("\n---\n") {
+            Some((header, input)) => (header, input),
+            None => bail!("expected the answers, then a line with ---, then the input"),
+        };
+
+        let mut expected = Vec::new();
+
+        for (line_no, line) in header.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            expected.push(parse_answer(line).with_context(|| format!("Line {}", line_no + 1))?);
+        }
+
+        if expected.is_empty() {
+            bail!("there are no expected answers");
+        }
+
+        Ok(Fixture {
+            day,
+            path: path.to_path_buf(),
+            input: input.to_string(),
+            expected,
+        })
+    }
+
+    /// Run the fixture, and return a description of each answer that was not
+    /// as expected.
+    pub fn run(&self) -> Vec<String> {
+        let solution = match registry::get(self.day) {
+            Some(solution) => solution,
+            None => return vec![format!("day {} is not solved", self.day)],
+        };
+
+        let parts: Vec<Part> = self.expected.iter().map(|&(part, _)| part).collect();
+
+        let results = match runner::run_day(
+            self.day,
+            solution,
+            &self.input,
+            &parts,
+            &Settings::default(),
+        ) {
+            Ok(results) => results,
+            Err(e) => return vec![e.diagnostic()],
+        };
+
+        results
+            .iter()
+            .zip(&self.expected)
+            .filter_map(|(r, (part, expected))| match r {
+                Ok(r) if r.answer.to_string() == *expected => None,
+                Ok(r) => Some(format!(
+                    "part {}: expected {}, got {}",
+                    part, expected, r.answer
+                )),
+                Err(e) => Some(e.to_string()),
+            })
+            .collect()
+    }
+}
+
+/// Find every fixture under `dir`, ordered by day and then file name.
+pub fn load_all(dir: &Path) -> Result<Vec<Fixture>, Error> {
+    let mut fixtures = Vec::new();
+
+    for entry in fs::read_dir(dir).with_context(|| format!("Could not read {}", dir.display()))? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+
+        let day = name
+            .strip_prefix("day")
+            .and_then(|d| d.parse().ok())
+            .ok_or_else(|| anyhow!("{} is not named dayN", entry.path().display()))?;
+
+        let mut paths: Vec<PathBuf> = fs::read_dir(entry.path())?
+            .map(|e| e.map(|e| e.path()))
+            .collect::<Result<_, _>>()?;
+        paths.retain(|p| p.extension().is_some_and(|e| e == "txt"));
+        paths.sort();
+
+        for path in paths {
+            fixtures.push(Fixture::load(day, &path)?);
+        }
+    }
+
+    fixtures.sort_by_key(|f| f.day);
+
+    Ok(fixtures)
+}
+
+#[test]
+fn all_fixtures() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+    let fixtures = load_all(&dir).unwrap();
+
+    let mut failures = Vec::new();
+
+    for fixture in &fixtures {
+        for failure in fixture.run() {
+            failures.push(format!("{}: {}", fixture.path.display(), failure));
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}\n", failures.join("\n"));
+
+    let uncovered: Vec<u32> = registry::days()
+        .filter(|&day| !fixtures.iter().any(|f| f.day == day))
+        .collect();
+    assert!(uncovered.is_empty(), "No fixtures for days {:?}", uncovered);
+}
@@ -0,0 +1,339 @@
+use crate::parse::{self, Line, ParseError};
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// The four orthogonal directions, as `(dx, dy)` with y increasing downwards.
+pub const DIRECTIONS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// The four orthogonal and four diagonal directions, clockwise from up-left.
+pub const DIRECTIONS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+/// A rectangular grid of cells, indexed by `(x, y)` with `(0, 0)` at the top
+/// left.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> T) -> Grid<T> {
+        let mut cells = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            for x in 0..width {
+                cells.push(f(x, y));
+            }
+        }
+
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parse a character map, converting each character with `cell`.
+    pub fn parse(s: &str, cell: impl FnMut(char) -> Option<T>) -> Result<Grid<T>, ParseError> {
+        let lines: Vec<Line> = parse::lines(s).collect();
+
+        if lines.is_empty() {
+            return Err(parse::missing(s, "a map"));
+        }
+
+        Grid::parse_lines(&lines, cell)
+    }
+
+    /// Parse a character map from some lines of a larger input.
+    pub fn parse_lines(
+        lines: &[Line],
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Grid<T>, ParseError> {
+        let width = lines.first().map_or(0, |l| l.text.chars().count());
+
+        if width == 0 {
+            return Err(match lines.first() {
+                Some(line) => line.error(1, "expected a row of squares"),
+                None => ParseError::new(1, "expected a map"),
+            });
+        }
+
+        let mut cells = Vec::with_capacity(width * lines.len());
+
+        for line in lines {
+            let row_width = line.text.chars().count();
+
+            if row_width != width {
+                return Err(line.error(
+                    row_width.min(width) + 1,
+                    format!("expected a row of width {}", width),
+                ));
+            }
+
+            for (x, c) in line.text.chars().enumerate() {
+                let value =
+                    cell(c).ok_or_else(|| line.error(x + 1, format!("unexpected '{}'", c)))?;
+                cells.push(value);
+            }
+        }
+
+        Ok(Grid {
+            width,
+            height: lines.len(),
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    /// The cell at `(x, y)`, or `None` if that is outside the grid.
+    pub fn get(&self, x: isize, y: isize) -> Option<&T> {
+        if self.contains(x, y) {
+            Some(&self.cells[x as usize + y as usize * self.width])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, x: isize, y: isize) -> Option<&mut T> {
+        if self.contains(x, y) {
+            Some(&mut self.cells[x as usize + y as usize * self.width])
+        } else {
+            None
+        }
+    }
+
+    /// Every position in the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Every cell in the grid with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// The positions next to `(x, y)` in the given directions that are inside
+    /// the grid.
+    pub fn neighbours<'a>(
+        &self,
+        x: usize,
+        y: usize,
+        directions: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        let (width, height) = (self.width as isize, self.height as isize);
+
+        directions.iter().filter_map(move |&(dx, dy)| {
+            let (x, y) = (x as isize + dx, y as isize + dy);
+
+            if x >= 0 && y >= 0 && x < width && y < height {
+                Some((x as usize, y as usize))
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.neighbours(x, y, &DIRECTIONS_4)
+    }
+
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.neighbours(x, y, &DIRECTIONS_8)
+    }
+
+    /// The positions from `(x, y)` in the direction `(dx, dy)`, not including
+    /// `(x, y)` itself, up to the edge of the grid.
+    pub fn ray(
+        &self,
+        x: usize,
+        y: usize,
+        dx: isize,
+        dy: isize,
+    ) -> impl Iterator<Item = (usize, usize)> {
+        let (width, height) = (self.width as isize, self.height as isize);
+        let (mut x, mut y) = (x as isize, y as isize);
+
+        std::iter::from_fn(move || {
+            x += dx;
+            y += dy;
+
+            if x >= 0 && y >= 0 && x < width && y < height && (dx, dy) != (0, 0) {
+                Some((x as usize, y as usize))
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(&mut f).collect(),
+        }
+    }
+
+    pub fn count(&self, mut f: impl FnMut(&T) -> bool) -> usize {
+        self.cells.iter().filter(|c| f(c)).count()
+    }
+
+    /// Render the grid as text, one line per row.
+    pub fn render(&self, mut f: impl FnMut(&T) -> char) -> String {
+        let mut s = String::with_capacity((self.width + 1) * self.height);
+
+        for row in self.cells.chunks(self.width.max(1)) {
+            s.extend(row.iter().map(&mut f));
+            s.push('\n');
+        }
+
+        s
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// The grid rotated a quarter turn clockwise.
+    pub fn rotate(&self) -> Grid<T> {
+        Grid::from_fn(self.height, self.width, |x, y| {
+            self[(y, self.height - 1 - x)].clone()
+        })
+    }
+
+    /// The grid mirrored left to right.
+    pub fn flip_h(&self) -> Grid<T> {
+        Grid::from_fn(self.width, self.height, |x, y| {
+            self[(self.width - 1 - x, y)].clone()
+        })
+    }
+
+    /// The grid flipped (if `flipped`) and then rotated clockwise by
+    /// `rotation` quarter turns.
+    pub fn transform(&self, rotation: usize, flipped: bool) -> Grid<T> {
+        let mut grid = if flipped { self.flip_h() } else { self.clone() };
+
+        for _ in 0..rotation % 4 {
+            grid = grid.rotate();
+        }
+
+        grid
+    }
+
+    /// All eight rotations and reflections of the grid.
+    pub fn orientations(&self) -> impl Iterator<Item = Grid<T>> + '_ {
+        [false, true]
+            .into_iter()
+            .flat_map(move |flipped| (0..4).map(move |rotation| self.transform(rotation, flipped)))
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(
+            x < self.width && y < self.height,
+            "({}, {}) is outside the grid",
+            x,
+            y
+        );
+        &self.cells[x + y * self.width]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(
+            x < self.width && y < self.height,
+            "({}, {}) is outside the grid",
+            x,
+            y
+        );
+        &mut self.cells[x + y * self.width]
+    }
+}
+
+/// Grids of booleans display as `#` and `.`.
+impl fmt::Display for Grid<bool> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.render(|&b| if b { '#' } else { '.' }))
+    }
+}
+
+/// Parse a map of `#` and `.` into a grid of booleans.
+pub fn parse_bool(s: &str) -> Result<Grid<bool>, ParseError> {
+    Grid::parse(s, bool_cell)
+}
+
+pub fn bool_cell(c: char) -> Option<bool> {
+    match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    }
+}
+
+#[test]
+fn grid_operations() {
+    let grid = parse_bool("#..\n.#.\n").unwrap();
+
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert!(grid[(0, 0)] && grid[(1, 1)] && !grid[(2, 1)]);
+    assert_eq!(grid.get(-1, 0), None);
+    assert_eq!(grid.get(2, 1), Some(&false));
+    assert_eq!(grid.count(|&b| b), 2);
+
+    let mut n: Vec<_> = grid.neighbours8(0, 0).collect();
+    n.sort_unstable();
+    assert_eq!(n, [(0, 1), (1, 0), (1, 1)]);
+    assert_eq!(grid.neighbours4(1, 1).count(), 3);
+    assert_eq!(grid.ray(0, 0, 1, 0).collect::<Vec<_>>(), [(1, 0), (2, 0)]);
+
+    assert_eq!(grid.rotate().to_string(), ".#\n#.\n..\n");
+    assert_eq!(grid.flip_h().to_string(), "..#\n.#.\n");
+    assert_eq!(grid.transform(4, false), grid);
+    assert_eq!(grid.orientations().count(), 8);
+
+    let e = parse_bool("#..\n.#\n").unwrap_err();
+    assert_eq!((e.line, e.column), (2, 3));
+    let e = parse_bool("#..\n.#x\n").unwrap_err();
+    assert_eq!((e.line, e.column), (2, 3));
+    let e = parse_bool("#.\n..\n\n").unwrap_err();
+    assert_eq!((e.line, e.column), (3, 1));
+    let e = parse_bool("\n#.\n").unwrap_err();
+    assert_eq!((e.line, e.column), (1, 1));
+    let e = parse_bool("#.\n...\n").unwrap_err();
+    assert_eq!((e.line, e.column), (2, 3));
+}
@@ -0,0 +1,312 @@
+use crate::parse::ParseError;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+/// A hexagon in a grid where each row is offset by half a hexagon from the
+/// one below, in axial coordinates. East is `q + 1`, and north-east is
+/// `r + 1`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+/// The same hexagon in cube coordinates, where `x + y + z == 0`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Cube {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl Hex {
+    pub const ORIGIN: Hex = Hex { q: 0, r: 0 };
+
+    pub const fn new(q: i32, r: i32) -> Hex {
+        Hex { q, r }
+    }
+
+    pub fn to_cube(self) -> Cube {
+        Cube {
+            x: self.q,
+            y: -self.q - self.r,
+            z: self.r,
+        }
+    }
+
+    pub fn from_cube(cube: Cube) -> Hex {
+        debug_assert_eq!(cube.x + cube.y + cube.z, 0);
+        Hex::new(cube.x, cube.z)
+    }
+
+    pub fn neighbour(self, direction: Direction) -> Hex {
+        self + direction.offset()
+    }
+
+    pub fn neighbours(self) -> [Hex; 6] {
+        Direction::ALL.map(|d| self.neighbour(d))
+    }
+
+    /// The number of steps to `other`.
+    pub fn distance(self, other: Hex) -> u32 {
+        (other - self).length()
+    }
+
+    /// The number of steps from the origin.
+    pub fn length(self) -> u32 {
+        let c = self.to_cube();
+        (c.x.unsigned_abs() + c.y.unsigned_abs() + c.z.unsigned_abs()) / 2
+    }
+
+    /// Rotate by 60 degrees clockwise around the origin.
+    pub fn rotate_cw(self) -> Hex {
+        let c = self.to_cube();
+        Hex::from_cube(Cube {
+            x: -c.y,
+            y: -c.z,
+            z: -c.x,
+        })
+    }
+
+    /// Rotate by 60 degrees anticlockwise around the origin.
+    pub fn rotate_ccw(self) -> Hex {
+        let c = self.to_cube();
+        Hex::from_cube(Cube {
+            x: -c.z,
+            y: -c.x,
+            z: -c.y,
+        })
+    }
+
+    /// The hexagons `radius` steps away, clockwise from the east.
+    pub fn ring(self, radius: u32) -> Vec<Hex> {
+        if radius == 0 {
+            return vec![self];
+        }
+
+        let mut ring = Vec::with_capacity(6 * radius as usize);
+        let mut hex = self + Direction::E.offset() * radius as i32;
+
+        // Walking south-west from the east corner keeps the same distance
+        for direction in Direction::ALL.map(|d| d.rotate_cw().rotate_cw()) {
+            for _ in 0..radius {
+                ring.push(hex);
+                hex = hex.neighbour(direction);
+            }
+        }
+
+        ring
+    }
+
+    /// The hexagons up to `radius` steps away, ring by ring from the centre.
+    pub fn spiral(self, radius: u32) -> Vec<Hex> {
+        (0..=radius).flat_map(|r| self.ring(r)).collect()
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+
+    fn add(self, rhs: Hex) -> Hex {
+        Hex::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+
+    fn sub(self, rhs: Hex) -> Hex {
+        Hex::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl Neg for Hex {
+    type Output = Hex;
+
+    fn neg(self) -> Hex {
+        Hex::new(-self.q, -self.r)
+    }
+}
+
+impl Mul<i32> for Hex {
+    type Output = Hex;
+
+    fn mul(self, n: i32) -> Hex {
+        Hex::new(self.q * n, self.r * n)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Direction {
+    E,
+    SE,
+    SW,
+    W,
+    NW,
+    NE,
+}
+
+impl Direction {
+    /// Every direction, clockwise from the east.
+    pub const ALL: [Direction; 6] = [
+        Direction::E,
+        Direction::SE,
+        Direction::SW,
+        Direction::W,
+        Direction::NW,
+        Direction::NE,
+    ];
+
+    pub fn offset(self) -> Hex {
+        match self {
+            Direction::E => Hex::new(1, 0),
+            Direction::SE => Hex::new(1, -1),
+            Direction::SW => Hex::new(0, -1),
+            Direction::W => Hex::new(-1, 0),
+            Direction::NW => Hex::new(-1, 1),
+            Direction::NE => Hex::new(0, 1),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Direction::E => "e",
+            Direction::SE => "se",
+            Direction::SW => "sw",
+            Direction::W => "w",
+            Direction::NW => "nw",
+            Direction::NE => "ne",
+        }
+    }
+
+    pub fn rotate_cw(self) -> Direction {
+        let i = Direction::ALL.iter().position(|&d| d == self).unwrap();
+        Direction::ALL[(i + 1) % 6]
+    }
+}
+
+/// Directions written one after another with no separators, like `esenee`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path(pub Vec<Direction>);
+
+impl Path {
+    /// Where the path ends if it starts at `start`.
+    pub fn end(&self, start: Hex) -> Hex {
+        self.0.iter().fold(start, |hex, &d| hex.neighbour(d))
+    }
+}
+
+impl FromStr for Path {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Path, ParseError> {
+        let mut directions = Vec::new();
+        let mut chars = s.char_indices();
+
+        while let Some((i, c)) = chars.next() {
+            let direction = match c {
+                'e' => Direction::E,
+                'w' => Direction::W,
+                'n' | 's' => match (c, chars.next().map(|(_, c)| c)) {
+                    ('s', Some('e')) => Direction::SE,
+                    ('s', Some('w')) => Direction::SW,
+                    ('n', Some('w')) => Direction::NW,
+                    ('n', Some('e')) => Direction::NE,
+                    _ => {
+                        return Err(ParseError::new(
+                            i + 1,
+                            "expected a direction: e, se, sw, w, nw or ne",
+                        ))
+                    }
+                },
+                _ => return Err(ParseError::new(i + 1, format!("unexpected '{}'", c))),
+            };
+
+            directions.push(direction);
+        }
+
+        Ok(Path(directions))
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for d in &self.0 {
+            f.write_str(d.name())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Draw the hexagons in the smallest box around `hexes`, with north at the
+/// top. Each row is offset by half a hexagon from the one above it, so
+/// hexagons are drawn in every other column.
+pub fn render(hexes: impl IntoIterator<Item = Hex>, mut f: impl FnMut(Hex) -> char) -> String {
+    // In doubled coordinates the hexagon to the east is two columns across,
+    // and the ones to the north-east and north-west are one column across
+    let column = |h: Hex| 2 * h.q + h.r;
+
+    let (mut min_x, mut max_x) = (i32::MAX, i32::MIN);
+    let (mut min_r, mut max_r) = (i32::MAX, i32::MIN);
+
+    for h in hexes {
+        min_x = min_x.min(column(h));
+        max_x = max_x.max(column(h));
+        min_r = min_r.min(h.r);
+        max_r = max_r.max(h.r);
+    }
+
+    let mut s = String::new();
+
+    for r in (min_r..=max_r).rev() {
+        let row: String = (min_x..=max_x)
+            .map(|x| {
+                if (x - r).rem_euclid(2) == 0 {
+                    f(Hex::new((x - r) / 2, r))
+                } else {
+                    ' '
+                }
+            })
+            .collect();
+
+        s += row.trim_end();
+        s.push('\n');
+    }
+
+    s
+}
+
+#[test]
+fn hex_coordinates() {
+    let path: Path = "nwwswee".parse().unwrap();
+    assert_eq!(path.end(Hex::ORIGIN), Hex::ORIGIN);
+    assert_eq!(path.to_string(), "nwwswee");
+    assert_eq!(
+        "esew".parse::<Path>().unwrap().end(Hex::ORIGIN),
+        Hex::new(1, -1)
+    );
+    assert_eq!("ens".parse::<Path>().unwrap_err().column, 2);
+
+    let h = Hex::new(2, -3);
+    assert_eq!(Hex::from_cube(h.to_cube()), h);
+    assert_eq!(h.length(), 3);
+    assert_eq!(h.distance(Hex::new(-1, 1)), 4);
+
+    assert_eq!(Direction::E.offset().rotate_cw(), Direction::SE.offset());
+    assert_eq!(Direction::NE.offset().rotate_cw(), Direction::E.offset());
+    assert_eq!(h.rotate_cw().rotate_ccw(), h);
+    assert_eq!((0..6).fold(h, |h, _| h.rotate_cw()), h);
+
+    let ring = h.ring(2);
+    assert_eq!(ring.len(), 12);
+    assert!(ring.iter().all(|&r| r.distance(h) == 2));
+    assert_eq!(h.spiral(2).len(), 19);
+
+    let tiles = [Hex::new(1, 0), Hex::new(0, 1), Hex::new(-1, 1)];
+    assert_eq!(
+        render(tiles, |h| if tiles.contains(&h) { '#' } else { '.' }),
+        "# #\n . #\n"
+    );
+}
@@ -0,0 +1,45 @@
+//! Solutions to the Advent of Code 2020 puzzles.
+//!
+//! Each day lives in its own module, which exposes its parser, domain types
+//! and the functions for both parts. Every day also implements
+//! [`solution::Solution`], and [`registry`] maps day numbers to them.
+
+pub mod answers;
+pub mod automaton;
+pub mod bench;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day2;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod fetch;
+pub mod fixtures;
+pub mod grid;
+pub mod hex;
+pub mod parse;
+pub mod point;
+pub mod registry;
+pub mod runner;
+pub mod solution;
+pub mod submit;
+pub mod visualize;
@@ -1,84 +1,684 @@
+use std::collections::HashSet;
 use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
 use std::process::exit;
+use std::time::{Duration, Instant};
 
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day2;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-mod day25;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
+use advent_of_code_2020::answers::{self, Answers};
+use advent_of_code_2020::bench::{self, Baseline};
+use advent_of_code_2020::day20::{self, Day20};
+use advent_of_code_2020::fetch::{self, Client, Fetched};
+use advent_of_code_2020::runner::Job;
+use advent_of_code_2020::solution::{Answer, Part, Settings, Solution};
+use advent_of_code_2020::submit::{self, SubmissionLog, Verdict};
+use advent_of_code_2020::visualize::{self, Screen};
+use advent_of_code_2020::{registry, runner};
+
+const USAGE: &str = "Usage:
+    advent-of-code-2020 <days> [--part <1|2>] [--input <path>] [--check]
+                        [--format <table|json>] [--jobs <n>] [--max-rounds <n>]
+    advent-of-code-2020 <days> --visualize [--part <1|2>] [--input <path>]
+                        [--delay <ms>] [--step] [--frames <path>]
+                        [--max-rounds <n>]
+    advent-of-code-2020 fetch <days> [--force] [--base-url <url>]
+    advent-of-code-2020 submit <day> <1|2> [--input <path>] [--base-url <url>]
+    advent-of-code-2020 bench <days> [--part <1|2>] [--iterations <n>]
+                        [--save <path>] [--baseline <path>] [--max-rounds <n>]
+    advent-of-code-2020 image [--input <path>] [--out <dir>] [--monsters]
+
+<days> is a day number, a range such as 1-10, a list such as 3,7,18, or all.
+
+Options:
+    --part <1|2>      Only run the given part.
+    --input <path>    Read the puzzle input from <path> instead of input/day<N>.
+                      Use - to read from stdin. Only valid for a single day.
+    --check           Compare the answers with the known-good ones and exit
+                      with an error if any of them do not match.
+    --answers <path>  Read the known-good answers from <path> instead of
+                      answers.toml.
+    --format <table|json>
+                      Print a table, which is the default, or one JSON object
+                      per line for each part with its answer, answer type and
+                      time.
+    --jobs <n>        Run up to <n> days and parts at the same time. The
+                      default is the number of CPUs. The results are always
+                      printed in order. Parts that run at the same time slow
+                      each other down, so use --jobs 1 to compare their times.
+    --max-rounds <n>  Give up on simulations that have not settled after <n>
+                      rounds. The default is 1000.
+
+--visualize animates each generation of the cellular automata in days 11, 17
+and 24 in the terminal, instead of printing the answers.
+
+Visualize options:
+    --delay <ms>      Show each generation for <ms> milliseconds. The default
+                      is 100.
+    --step            Wait for Enter after each generation instead.
+    --frames <path>   Also write every generation to <path>.
+
+fetch downloads the puzzle inputs to input/day<N>, using the session cookie
+from the AOC_SESSION environment variable or a file called AOC_SESSION.
+
+Fetch options:
+    --force           Download inputs even if they are already there.
+    --base-url <url>  Use <url> instead of https://adventofcode.com/2020.
+
+submit computes the answer to one part and submits it. Every submission is
+recorded in submissions.log, and answers that are known to be wrong are never
+submitted again.
+
+Submit options:
+    --input <path>    Read the puzzle input from <path> instead of input/day<N>.
+    --base-url <url>  Use <url> instead of https://adventofcode.com/2020.
+    --log <path>      Record submissions in <path> instead of submissions.log.
+
+bench times parsing and each part separately and reports the minimum, median
+and maximum times. Parts that fail are reported as errors, and are left out of
+the table and the baseline.
+
+Bench options:
+    --part <1|2>           Only run the given part.
+    --iterations <n>       Run everything <n> times. The default is 5.
+    --save <path>          Save the times to <path> as a JSON baseline.
+    --baseline <path>      Compare the median times with a saved baseline and
+                           exit with an error if any of them regressed.
+    --threshold <percent>  How much slower than the baseline counts as a
+                           regression. The default is 10.
+    --max-rounds <n>       Give up on simulations that have not settled after
+                           <n> rounds. The default is 1000.
+
+image assembles the day 20 tiles and writes the image to image.txt, image.pbm
+and image.pgm, and where each tile went to tiles.txt.
+
+Image options:
+    --input <path>    Read the puzzle input from <path> instead of input/day20.
+    --out <dir>       Write the files to <dir> instead of the current directory.
+    --monsters        Show the sea monsters as O in image.txt and in white in
+                      image.pgm.";
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let args: Vec<String> = env::args().skip(1).collect();
 
-    if args.len() != 2 {
-        eprintln!("Error: incorrect number of arguments");
-        eprintln!();
-        eprintln!("Usage:");
-        eprintln!("    advent-of-code-2020 <day>");
-        exit(1);
+    if args.iter().any(|a| a == "--help" || a == "-h") {
+        println!("{}", USAGE);
+        return;
+    }
+
+    match args.first().map(|a| a.as_str()) {
+        Some("fetch") => fetch_main(args.into_iter().skip(1)),
+        Some("submit") => submit_main(args.into_iter().skip(1)),
+        Some("bench") => bench_main(args.into_iter().skip(1)),
+        Some("image") => image_main(args.into_iter().skip(1)),
+        _ => run_main(args.into_iter()),
     }
+}
+
+fn run_main(mut args: impl Iterator<Item = String>) {
+    let mut days = None;
+    let mut parts = Part::BOTH.to_vec();
+    let mut input_path = None;
+    let mut check = false;
+    let mut answers_path = answers::DEFAULT_PATH.to_string();
+    let mut json = false;
+    let mut threads = runner::default_threads();
+    let mut visualize = None;
+    let mut options = visualize::Options::default();
+    let mut settings = Settings::default();
+
+    while let Some(arg) = args.next() {
+        if arg == "--visualize" {
+            visualize = Some(true);
+        } else if arg == "--delay" {
+            options.delay = match args.next().map(|n| n.parse()) {
+                Some(Ok(ms)) => Duration::from_millis(ms),
+                _ => usage_error("--delay needs a number of milliseconds"),
+            };
+            visualize.get_or_insert(false);
+        } else if arg == "--step" {
+            options.step = true;
+            visualize.get_or_insert(false);
+        } else if arg == "--frames" {
+            options.frames = Some(
+                args.next()
+                    .unwrap_or_else(|| usage_error("--frames needs a path"))
+                    .into(),
+            );
+            visualize.get_or_insert(false);
+        } else if arg == "--input" {
+            input_path = Some(
+                args.next()
+                    .unwrap_or_else(|| usage_error("--input needs a path")),
+            );
+        } else if arg == "--check" {
+            check = true;
+        } else if arg == "--jobs" {
+            threads = match args.next().map(|n| n.parse()) {
+                Some(Ok(n)) if n > 0 => n,
+                _ => usage_error("--jobs needs a positive number"),
+            };
+        } else if arg == "--max-rounds" {
+            settings.max_rounds = match args.next().map(|n| n.parse()) {
+                Some(Ok(n)) if n > 0 => Some(n),
+                _ => usage_error("--max-rounds needs a positive number"),
+            };
+        } else if arg == "--format" {
+            json = match args.next().as_deref() {
+                Some("table") => false,
+                Some("json") => true,
+                _ => usage_error("--format needs to be table or json"),
+            };
+        } else if arg == "--answers" {
+            answers_path = args
+                .next()
+                .unwrap_or_else(|| usage_error("--answers needs a path"));
+        } else if arg == "--part" {
+            parts = match args.next().as_deref() {
+                Some("1") => vec![Part::One],
+                Some("2") => vec![Part::Two],
+                _ => usage_error("--part needs to be 1 or 2"),
+            };
+        } else if days.is_none() {
+            days = Some(arg);
+        } else {
+            usage_error("incorrect number of arguments");
+        }
+    }
+
+    let days = parse_days(days);
+
+    if input_path.is_some() && days.len() != 1 {
+        usage_error("--input can only be used with a single day");
+    }
+
+    match visualize {
+        Some(true) => return visualize_main(&days, &parts, input_path, options, &settings),
+        Some(false) => usage_error("--delay, --step and --frames need --visualize"),
+        None => {}
+    }
+
+    let answers = if check {
+        Some(Answers::load(&answers_path).unwrap_or_else(|e| {
+            eprintln!("Error: {:#}", e);
+            exit(1);
+        }))
+    } else {
+        None
+    };
+
+    let mut jobs = Vec::new();
+    let mut errors = Vec::new();
+
+    for day in days {
+        let input_path = input_path
+            .clone()
+            .unwrap_or_else(|| runner::input_path(day).display().to_string());
+
+        match read_input(&input_path) {
+            Ok(input) => jobs.push(Job {
+                day,
+                solution: registry::get(day).unwrap(),
+                input,
+            }),
+            Err(e) => {
+                let error = format!("could not read input from {}: {}", input_path, e);
+                eprintln!("Error: day {}: {}", day, error);
+                errors.extend(parts.iter().map(|&part| (day, part, error.clone())));
+            }
+        }
+    }
+
+    let mut results = Vec::new();
+
+    let start = Instant::now();
+    let outcomes = runner::run_days(&jobs, &parts, threads, &settings);
+    let elapsed = start.elapsed();
+
+    for (job, result) in jobs.iter().zip(outcomes) {
+        match result {
+            Ok(r) => {
+                for part in r {
+                    match part {
+                        Ok(r) => results.push(r),
+                        Err(e) => {
+                            eprintln!("Error: {}", e);
+                            errors.push((e.day, e.part, e.message));
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("{}", e.diagnostic());
+                errors.extend(parts.iter().map(|&part| (job.day, part, e.to_string())));
+            }
+        }
+    }
+
+    let not_run = errors.len();
+
+    if json {
+        let not_ok = runner::print_json(&results, &errors, answers.as_ref());
+
+        if not_run > 0 || not_ok > 0 {
+            exit(1);
+        }
 
-    if args[1] == "--help" || args[1] == "-h" {
-        println!("Usage:");
-        println!("    advent-of-code-2020 <day>");
         return;
     }
 
-    let day: u32 = args[1].parse().unwrap_or_else(|_| {
-        eprintln!("Invalid day");
+    let not_ok = runner::print_table(&results, answers.as_ref(), elapsed);
+
+    if check {
+        println!();
+        println!(
+            "{} of {} answers ok",
+            results.len() - not_ok,
+            results.len() + not_run
+        );
+    }
+
+    if not_run > 0 || not_ok > 0 {
+        exit(1);
+    }
+}
+
+fn visualize_main(
+    days: &[u32],
+    parts: &[Part],
+    input_path: Option<String>,
+    options: visualize::Options,
+    settings: &Settings,
+) {
+    if let Some(&day) = days.iter().find(|d| !visualize::DAYS.contains(d)) {
+        usage_error(&format!(
+            "day {} cannot be visualized, only days 11, 17 and 24 can",
+            day
+        ));
+    }
+
+    if options.step && input_path.as_deref() == Some("-") {
+        usage_error("--step reads from stdin, so it cannot be used with --input -");
+    }
+
+    let mut screen = Screen::new(options).unwrap_or_else(|e| {
+        eprintln!("Error: {:#}", e);
+        exit(1);
+    });
+
+    for &day in days {
+        let input_path = input_path
+            .clone()
+            .unwrap_or_else(|| runner::input_path(day).display().to_string());
+        let input = read_input(&input_path).unwrap_or_else(|e| {
+            eprintln!(
+                "Error: day {}: could not read input from {}: {}",
+                day, input_path, e
+            );
+            exit(1);
+        });
+
+        for &part in parts {
+            if let Err(e) = visualize::visualize(&mut screen, day, part, &input, settings) {
+                eprintln!("Error: {:#}", e);
+                exit(1);
+            }
+        }
+    }
+}
+
+fn fetch_main(mut args: impl Iterator<Item = String>) {
+    let mut days = None;
+    let mut force = false;
+    let mut base_url = fetch::DEFAULT_BASE_URL.to_string();
+
+    while let Some(arg) = args.next() {
+        if arg == "--force" {
+            force = true;
+        } else if arg == "--base-url" {
+            base_url = args
+                .next()
+                .unwrap_or_else(|| usage_error("--base-url needs a URL"));
+        } else if days.is_none() {
+            days = Some(arg);
+        } else {
+            usage_error("incorrect number of arguments");
+        }
+    }
+
+    let days = parse_days(days);
+    let session = fetch::session().unwrap_or_else(|e| {
+        eprintln!("Error: {:#}", e);
+        exit(1);
+    });
+    let client = Client::new(&base_url, &session);
+    let mut failed = false;
+
+    for day in days {
+        let path = runner::input_path(day);
+
+        match fetch::fetch(&client, day, &path, force) {
+            Ok(Fetched::Downloaded) => println!("Day {}: downloaded {}", day, path.display()),
+            Ok(Fetched::Cached) => println!(
+                "Day {}: {} already exists, use --force to download it again",
+                day,
+                path.display()
+            ),
+            Err(e) => {
+                eprintln!("Error: day {}: {:#}", day, e);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        exit(1);
+    }
+}
+
+fn submit_main(mut args: impl Iterator<Item = String>) {
+    let mut positional = Vec::new();
+    let mut input_path = None;
+    let mut base_url = fetch::DEFAULT_BASE_URL.to_string();
+    let mut log_path = submit::DEFAULT_LOG_PATH.to_string();
+
+    while let Some(arg) = args.next() {
+        if arg == "--input" {
+            input_path = Some(
+                args.next()
+                    .unwrap_or_else(|| usage_error("--input needs a path")),
+            );
+        } else if arg == "--base-url" {
+            base_url = args
+                .next()
+                .unwrap_or_else(|| usage_error("--base-url needs a URL"));
+        } else if arg == "--log" {
+            log_path = args
+                .next()
+                .unwrap_or_else(|| usage_error("--log needs a path"));
+        } else {
+            positional.push(arg);
+        }
+    }
+
+    let (day, part) = match positional.as_slice() {
+        [day, part] => (day, part),
+        _ => usage_error("incorrect number of arguments"),
+    };
+
+    let days = parse_days(Some(day.clone()));
+    let day = match days.as_slice() {
+        [day] => *day,
+        _ => usage_error("submit needs a single day"),
+    };
+    let part = match part.as_str() {
+        "1" => Part::One,
+        "2" => Part::Two,
+        _ => usage_error("the part needs to be 1 or 2"),
+    };
+
+    let input_path = input_path.unwrap_or_else(|| runner::input_path(day).display().to_string());
+    let input = read_input(&input_path).unwrap_or_else(|e| {
+        eprintln!(
+            "Error: day {}: could not read input from {}: {}",
+            day, input_path, e
+        );
+        exit(1);
+    });
+
+    let results = runner::run_day(
+        day,
+        registry::get(day).unwrap(),
+        &input,
+        &[part],
+        &Settings::default(),
+    )
+    .unwrap_or_else(|e| {
+        eprintln!("{}", e.diagnostic());
         exit(1);
     });
 
-    match day {
-        1 => day1::solve(),
-        2 => day2::solve(),
-        3 => day3::solve(),
-        4 => day4::solve(),
-        5 => day5::solve(),
-        6 => day6::solve(),
-        7 => day7::solve(),
-        8 => day8::solve(),
-        9 => day9::solve(),
-        10 => day10::solve(),
-        11 => day11::solve(),
-        12 => day12::solve(),
-        13 => day13::solve(),
-        14 => day14::solve(),
-        15 => day15::solve(),
-        16 => day16::solve(),
-        17 => day17::solve(),
-        18 => day18::solve(),
-        19 => day19::solve(),
-        20 => day20::solve(),
-        21 => day21::solve(),
-        22 => day22::solve(),
-        23 => day23::solve(),
-        24 => day24::solve(),
-        25 => day25::solve(),
-
-        _ => {
-            eprintln!("I haven't solved that day yet!");
+    let answer = match &results[0] {
+        Ok(result) => &result.answer,
+        Err(e) => {
+            eprintln!("Error: {}", e);
             exit(1);
         }
     };
+
+    if *answer == Answer::Unsolved {
+        eprintln!("Error: day {} part {} is not solved yet", day, part);
+        exit(1);
+    }
+
+    let session = fetch::session().unwrap_or_else(|e| {
+        eprintln!("Error: {:#}", e);
+        exit(1);
+    });
+    let client = Client::new(&base_url, &session);
+    let mut log = SubmissionLog::load(log_path.as_ref()).unwrap_or_else(|e| {
+        eprintln!("Error: {:#}", e);
+        exit(1);
+    });
+
+    println!("Submitting {} for day {} part {}", answer, day, part);
+
+    let response = submit::submit(&client, &mut log, day, part, &answer.to_string())
+        .unwrap_or_else(|e| {
+            eprintln!("Error: {:#}", e);
+            exit(1);
+        });
+
+    println!("{}", response.message);
+
+    if response.verdict != Verdict::Right {
+        exit(1);
+    }
+}
+
+fn bench_main(mut args: impl Iterator<Item = String>) {
+    let mut days = None;
+    let mut parts = Part::BOTH.to_vec();
+    let mut iterations = bench::DEFAULT_ITERATIONS;
+    let mut save_path = None;
+    let mut baseline_path = None;
+    let mut threshold = bench::DEFAULT_THRESHOLD;
+    let mut settings = Settings::default();
+
+    while let Some(arg) = args.next() {
+        if arg == "--part" {
+            parts = match args.next().as_deref() {
+                Some("1") => vec![Part::One],
+                Some("2") => vec![Part::Two],
+                _ => usage_error("--part needs to be 1 or 2"),
+            };
+        } else if arg == "--iterations" {
+            iterations = match args.next().map(|n| n.parse()) {
+                Some(Ok(n)) if n > 0 => n,
+                _ => usage_error("--iterations needs a positive number"),
+            };
+        } else if arg == "--save" {
+            save_path = Some(
+                args.next()
+                    .unwrap_or_else(|| usage_error("--save needs a path")),
+            );
+        } else if arg == "--baseline" {
+            baseline_path = Some(
+                args.next()
+                    .unwrap_or_else(|| usage_error("--baseline needs a path")),
+            );
+        } else if arg == "--threshold" {
+            threshold = match args.next().map(|n| n.parse::<f64>()) {
+                Some(Ok(n)) if n >= 0.0 => n / 100.0,
+                _ => usage_error("--threshold needs a percentage"),
+            };
+        } else if arg == "--max-rounds" {
+            settings.max_rounds = match args.next().map(|n| n.parse()) {
+                Some(Ok(n)) if n > 0 => Some(n),
+                _ => usage_error("--max-rounds needs a positive number"),
+            };
+        } else if days.is_none() {
+            days = Some(arg);
+        } else {
+            usage_error("incorrect number of arguments");
+        }
+    }
+
+    let days = parse_days(days);
+
+    // Load the baseline first, so that --save can overwrite it
+    let baseline = baseline_path.map(|path| {
+        Baseline::load(&path).unwrap_or_else(|e| {
+            eprintln!("Error: {:#}", e);
+            exit(1);
+        })
+    });
+
+    let mut results = Vec::new();
+    let mut not_run = 0;
+
+    for day in days {
+        let solution = registry::get(day).unwrap();
+        let input_path = runner::input_path(day);
+
+        let input = match fs::read_to_string(&input_path) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!(
+                    "Error: day {}: could not read input from {}: {}",
+                    day,
+                    input_path.display(),
+                    e
+                );
+                not_run += 1;
+                continue;
+            }
+        };
+
+        match bench::bench_day(day, solution, &input, &parts, iterations, &settings) {
+            Ok(outcomes) => {
+                for outcome in outcomes {
+                    match outcome {
+                        Ok(r) => results.push(r),
+                        Err(e) => {
+                            eprintln!("Error: {}", e);
+                            not_run += 1;
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("{}", e.diagnostic());
+                not_run += 1;
+            }
+        }
+    }
+
+    let regressions = bench::print_table(&results, baseline.as_ref(), threshold);
+
+    if let Some(path) = save_path {
+        if let Err(e) = Baseline::save(&path, &results) {
+            eprintln!("Error: {:#}", e);
+            exit(1);
+        }
+    }
+
+    if baseline.is_some() {
+        println!();
+        println!("{} regressions", regressions);
+    }
+
+    if not_run > 0 || regressions > 0 {
+        exit(1);
+    }
+}
+
+fn parse_days(days: Option<String>) -> Vec<u32> {
+    let days = days.unwrap_or_else(|| usage_error("incorrect number of arguments"));
+
+    runner::parse_days(&days).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        exit(1);
+    })
+}
+
+fn image_main(mut args: impl Iterator<Item = String>) {
+    let mut input_path = runner::input_path(20).display().to_string();
+    let mut out = PathBuf::from(".");
+    let mut monsters = false;
+
+    while let Some(arg) = args.next() {
+        if arg == "--input" {
+            input_path = args
+                .next()
+                .unwrap_or_else(|| usage_error("--input needs a path"));
+        } else if arg == "--out" {
+            out = args
+                .next()
+                .unwrap_or_else(|| usage_error("--out needs a directory"))
+                .into();
+        } else if arg == "--monsters" {
+            monsters = true;
+        } else {
+            usage_error("incorrect number of arguments");
+        }
+    }
+
+    let input = read_input(&input_path).unwrap_or_else(|e| {
+        eprintln!(
+            "Error: day 20: could not read input from {}: {}",
+            input_path, e
+        );
+        exit(1);
+    });
+    let tiles = Day20.parse(&input).unwrap_or_else(|e| {
+        eprintln!("{}", e.in_day(20).diagnostic());
+        exit(1);
+    });
+    let grid = day20::assemble(&tiles).unwrap_or_else(|e| {
+        eprintln!("Error: day 20: {:#}", e);
+        exit(1);
+    });
+
+    let image = day20::stitch(&grid);
+    let highlighted = if monsters {
+        day20::sea_monsters(&image)
+    } else {
+        HashSet::new()
+    };
+
+    let files = [
+        ("image.txt", day20::render(&image, &highlighted)),
+        ("image.pbm", day20::to_pbm(&image)),
+        ("image.pgm", day20::to_pgm(&image, &highlighted)),
+        ("tiles.txt", day20::placements(&grid)),
+    ];
+
+    for (name, contents) in files {
+        let path = out.join(name);
+
+        if let Err(e) = fs::write(&path, contents) {
+            eprintln!("Error: could not write {}: {}", path.display(), e);
+            exit(1);
+        }
+
+        println!("Wrote {}", path.display());
+    }
+}
+
+fn read_input(path: &str) -> io::Result<String> {
+    if path == "-" {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        Ok(input)
+    } else {
+        fs::read_to_string(path)
+    }
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("Error: {}", message);
+    eprintln!();
+    eprintln!("{}", USAGE);
+    exit(1);
 }
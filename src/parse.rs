@@ -0,0 +1,220 @@
+use regex::{Captures, Regex};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// An error in a puzzle input, with the location of the problem.
+///
+/// Line and column numbers start at 1. A line of 0 means that the location
+/// is not known yet, which is the case for errors from `FromStr`
+/// implementations that only see a single line. The caller fills it in with
+/// `at_line`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: Option<u32>,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(column: usize, message: impl Into<String>) -> ParseError {
+        ParseError {
+            day: None,
+            line: 0,
+            column,
+            text: String::new(),
+            message: message.into(),
+        }
+    }
+
+    /// Set the line the error is on, unless it is already known.
+    pub fn at_line(mut self, line: Line) -> ParseError {
+        if self.line == 0 {
+            self.line = line.number;
+            self.text = line.text.to_string();
+        }
+
+        self
+    }
+
+    /// Move the error right, for errors from parsing part of a line.
+    pub fn offset(mut self, columns: usize) -> ParseError {
+        self.column += columns;
+        self
+    }
+
+    pub fn in_day(mut self, day: u32) -> ParseError {
+        self.day = Some(day);
+        self
+    }
+
+    /// A multi-line description of the error that shows the offending line.
+    pub fn diagnostic(&self) -> String {
+        let mut s = format!("error: {}", self);
+
+        if self.line != 0 {
+            let number = self.line.to_string();
+            let margin = " ".repeat(number.len());
+            let caret = " ".repeat(self.column.saturating_sub(1));
+
+            s += &format!("\n{} |", margin);
+            s += &format!("\n{} | {}", number, self.text);
+            s += &format!("\n{} | {}^", margin, caret);
+        }
+
+        s
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {}, ", day)?;
+        }
+
+        if self.line != 0 {
+            write!(f, "line {}, ", self.line)?;
+        }
+
+        write!(f, "column {}: {}", self.column, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+/// A line of the input, with its line number.
+#[derive(Debug, Clone, Copy)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    pub fn error(&self, column: usize, message: impl Into<String>) -> ParseError {
+        ParseError::new(column, message).at_line(*self)
+    }
+
+    /// An error about `part`, which must be a slice of this line.
+    pub fn error_at(&self, part: &str, message: impl Into<String>) -> ParseError {
+        self.error(column(self.text, part), message)
+    }
+
+    pub fn parse<T: FromStr<Err = ParseError>>(&self) -> Result<T, ParseError> {
+        self.text.parse().map_err(|e: ParseError| e.at_line(*self))
+    }
+
+    pub fn parse_number<T: FromStr>(&self) -> Result<T, ParseError> {
+        number(self.text, self.text.trim()).map_err(|e| e.at_line(*self))
+    }
+}
+
+/// The lines of the input, numbered from 1.
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    input.lines().enumerate().map(|(i, text)| Line {
+        number: i + 1,
+        text,
+    })
+}
+
+/// Split the input into sections separated by blank lines.
+pub fn sections(input: &str) -> Vec<Vec<Line<'_>>> {
+    let mut sections = Vec::new();
+    let mut section = Vec::new();
+
+    for line in lines(input) {
+        if line.text.trim().is_empty() {
+            if !section.is_empty() {
+                sections.push(section);
+                section = Vec::new();
+            }
+        } else {
+            section.push(line);
+        }
+    }
+
+    if !section.is_empty() {
+        sections.push(section);
+    }
+
+    sections
+}
+
+/// Parse every line of the input with `FromStr`.
+pub fn parse_lines<T: FromStr<Err = ParseError>>(input: &str) -> Result<Vec<T>, ParseError> {
+    lines(input).map(|l| l.parse()).collect()
+}
+
+/// An error for input that ended before something that was expected.
+pub fn missing(input: &str, what: &str) -> ParseError {
+    let line = Line {
+        number: input.lines().count() + 1,
+        text: "",
+    };
+
+    line.error(1, format!("unexpected end of input, expected {}", what))
+}
+
+/// The column at which `part` starts, where `part` is a slice of `line`.
+pub fn column(line: &str, part: &str) -> usize {
+    let offset = (part.as_ptr() as usize).wrapping_sub(line.as_ptr() as usize);
+
+    if offset <= line.len() {
+        offset + 1
+    } else {
+        1
+    }
+}
+
+/// Parse `part`, a slice of `line`, as a number.
+pub fn number<T: FromStr>(line: &str, part: &str) -> Result<T, ParseError> {
+    part.parse().map_err(|_| {
+        ParseError::new(
+            column(line, part),
+            format!("expected a number, found \"{}\"", part),
+        )
+    })
+}
+
+/// Match `line` against `re`, which should be anchored at both ends.
+pub fn captures<'t>(re: &Regex, line: &'t str, expected: &str) -> Result<Captures<'t>, ParseError> {
+    re.captures(line)
+        .ok_or_else(|| ParseError::new(1, format!("expected {}", expected)))
+}
+
+/// Parse capture group `i` as a number.
+pub fn capture_number<T: FromStr>(line: &str, caps: &Captures, i: usize) -> Result<T, ParseError> {
+    number(line, caps.get(i).unwrap().as_str())
+}
+
+#[test]
+fn error_locations() {
+    let input = "12\n\n3x\n";
+    let lines: Vec<Line> = lines(input).collect();
+
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[2].number, 3);
+
+    let e = lines[2].parse_number::<u32>().unwrap_err().in_day(1);
+    assert_eq!((e.line, e.column), (3, 1));
+    assert_eq!(
+        e.to_string(),
+        "day 1, line 3, column 1: expected a number, found \"3x\""
+    );
+    assert_eq!(
+        e.diagnostic(),
+        "error: day 1, line 3, column 1: expected a number, found \"3x\"\n  |\n3 | 3x\n  | ^"
+    );
+
+    let line = "a: 12, 3y";
+    let part = line.split(", ").nth(1).unwrap();
+    assert_eq!(column(line, part), 8);
+    assert_eq!(number::<u32>(line, part).unwrap_err().column, 8);
+
+    let sections = sections("a\nb\n\n\nc\n");
+    assert_eq!(sections.len(), 2);
+    assert_eq!(sections[1][0].number, 5);
+
+    assert_eq!(missing("a\nb\n", "c").line, 3);
+}
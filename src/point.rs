@@ -0,0 +1,107 @@
+use std::ops::{Add, Index, IndexMut, Sub};
+
+/// A point with D integer coordinates.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PointN<const D: usize>(pub [i32; D]);
+
+impl<const D: usize> PointN<D> {
+    pub const ORIGIN: PointN<D> = PointN([0; D]);
+
+    /// The point `(x, y, 0, 0, ...)`.
+    pub fn from_2d(x: i32, y: i32) -> PointN<D> {
+        let mut p = PointN::ORIGIN;
+        p[0] = x;
+        p[1] = y;
+        p
+    }
+
+    pub fn coords(&self) -> &[i32; D] {
+        &self.0
+    }
+
+    /// The 3^D - 1 offsets to the points that differ by at most one in every
+    /// coordinate.
+    pub fn moore_offsets() -> Vec<PointN<D>> {
+        let mut offsets = vec![PointN::ORIGIN];
+
+        for i in 0..D {
+            offsets = offsets
+                .into_iter()
+                .flat_map(|o| {
+                    (-1..=1).map(move |d| {
+                        let mut o = o;
+                        o[i] = d;
+                        o
+                    })
+                })
+                .collect();
+        }
+
+        offsets.retain(|&o| o != PointN::ORIGIN);
+        offsets
+    }
+
+    /// The 2 * D offsets to the points that differ by one in a single
+    /// coordinate.
+    pub fn von_neumann_offsets() -> Vec<PointN<D>> {
+        let mut offsets = Vec::new();
+
+        for i in 0..D {
+            for d in [-1, 1] {
+                let mut o = PointN::ORIGIN;
+                o[i] = d;
+                offsets.push(o);
+            }
+        }
+
+        offsets
+    }
+}
+
+impl<const D: usize> Default for PointN<D> {
+    fn default() -> PointN<D> {
+        PointN::ORIGIN
+    }
+}
+
+impl<const D: usize> From<[i32; D]> for PointN<D> {
+    fn from(coords: [i32; D]) -> PointN<D> {
+        PointN(coords)
+    }
+}
+
+impl<const D: usize> Index<usize> for PointN<D> {
+    type Output = i32;
+
+    fn index(&self, i: usize) -> &i32 {
+        &self.0[i]
+    }
+}
+
+impl<const D: usize> IndexMut<usize> for PointN<D> {
+    fn index_mut(&mut self, i: usize) -> &mut i32 {
+        &mut self.0[i]
+    }
+}
+
+impl<const D: usize> Add for PointN<D> {
+    type Output = PointN<D>;
+
+    fn add(mut self, rhs: PointN<D>) -> PointN<D> {
+        for i in 0..D {
+            self.0[i] += rhs.0[i];
+        }
+        self
+    }
+}
+
+impl<const D: usize> Sub for PointN<D> {
+    type Output = PointN<D>;
+
+    fn sub(mut self, rhs: PointN<D>) -> PointN<D> {
+        for i in 0..D {
+            self.0[i] -= rhs.0[i];
+        }
+        self
+    }
+}
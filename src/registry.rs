@@ -0,0 +1,44 @@
+use crate::solution::DynSolution;
+use crate::{
+    day1, day10, day11, day12, day13, day14, day15, day16, day17, day18, day19, day2, day20, day21,
+    day22, day23, day24, day25, day3, day4, day5, day6, day7, day8, day9,
+};
+
+static SOLUTIONS: [&dyn DynSolution; 25] = [
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+    &day6::Day6,
+    &day7::Day7,
+    &day8::Day8,
+    &day9::Day9,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+    &day14::Day14,
+    &day15::Day15,
+    &day16::Day16,
+    &day17::Day17,
+    &day18::Day18,
+    &day19::Day19,
+    &day20::Day20,
+    &day21::Day21,
+    &day22::Day22,
+    &day23::Day23,
+    &day24::Day24,
+    &day25::Day25,
+];
+
+/// Look up the solution for a day, if there is one.
+pub fn get(day: u32) -> Option<&'static dyn DynSolution> {
+    let idx = day.checked_sub(1)? as usize;
+    SOLUTIONS.get(idx).copied()
+}
+
+/// All the days that have a solution, in order.
+pub fn days() -> impl Iterator<Item = u32> {
+    1..=SOLUTIONS.len() as u32
+}
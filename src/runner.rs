@@ -0,0 +1,659 @@
+use crate::answers::{Answers, Status};
+use crate::parse::ParseError;
+use crate::registry;
+use crate::solution::{Answer, DynSolution, Output, ParsedInput, Part, Settings};
+use anyhow::{anyhow, bail, Error};
+use serde_json::{json, Value};
+use std::any::Any;
+use std::collections::VecDeque;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct PartResult {
+    pub day: u32,
+    pub part: Part,
+    pub answer: Answer,
+    /// Numbers the solution reported besides its answer.
+    pub stats: Vec<(&'static str, u64)>,
+    pub time: Duration,
+}
+
+/// A part that did not finish, because the solution failed or panicked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartError {
+    pub day: u32,
+    pub part: Part,
+    pub message: String,
+}
+
+impl fmt::Display for PartError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "day {}, part {}: {}", self.day, self.part, self.message)
+    }
+}
+
+/// The results for one day. The whole day fails if its input cannot be
+/// parsed, and otherwise each part succeeds or fails on its own.
+pub type DayResults = Result<Vec<PartOutcome>, ParseError>;
+
+pub type PartOutcome = Result<PartResult, PartError>;
+
+/// Parse a selection of days such as `all`, `5`, `1-10` or `3,7,18-20`.
+pub fn parse_days(spec: &str) -> Result<Vec<u32>, Error> {
+    if spec == "all" {
+        return Ok(registry::days().collect());
+    }
+
+    let mut days = Vec::new();
+
+    for item in spec.split(',') {
+        let (first, last) = match item.split_once('-') {
+            Some((first, last)) => (parse_day(first)?, parse_day(last)?),
+            None => {
+                let day = parse_day(item)?;
+                (day, day)
+            }
+        };
+
+        if first > last {
+            bail!("Invalid range of days: {}", item);
+        }
+
+        for day in first..=last {
+            if !days.contains(&day) {
+                days.push(day);
+            }
+        }
+    }
+
+    days.sort_unstable();
+
+    Ok(days)
+}
+
+fn parse_day(s: &str) -> Result<u32, Error> {
+    let day = s
+        .trim()
+        .parse()
+        .map_err(|_| anyhow!("Invalid day: {}", s))?;
+
+    if registry::get(day).is_none() {
+        bail!("I haven't solved day {} yet!", day);
+    }
+
+    Ok(day)
+}
+
+/// Where the input for a day is kept.
+pub fn input_path(day: u32) -> PathBuf {
+    PathBuf::from(format!("input/day{}", day))
+}
+
+/// Parse the input for one day and run the selected parts on it.
+pub fn run_day(
+    day: u32,
+    solution: &dyn DynSolution,
+    input: &str,
+    parts: &[Part],
+    settings: &Settings,
+) -> DayResults {
+    let job = Job {
+        day,
+        solution,
+        input: input.to_string(),
+    };
+
+    run_days(&[job], parts, 1, settings).pop().unwrap()
+}
+
+/// Run `f`, and turn a panic into its message.
+fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| panic_message(&*payload))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "the solution panicked".to_string()
+    }
+}
+
+/// Run one part of a day, and turn an error or a panic into a `PartError`.
+pub fn run_part(
+    day: u32,
+    solution: &dyn DynSolution,
+    input: &ParsedInput,
+    part: Part,
+    settings: &Settings,
+) -> Result<Output, PartError> {
+    let error = |message| PartError { day, part, message };
+
+    match catch_panic(|| solution.part(input, part, settings)) {
+        Ok(Ok(output)) => Ok(output),
+        Ok(Err(e)) => Err(error(format!("{:#}", e))),
+        Err(message) => Err(error(format!("panicked: {}", message))),
+    }
+}
+
+/// One day's work for `run_days`.
+pub struct Job<'a> {
+    pub day: u32,
+    pub solution: &'a dyn DynSolution,
+    pub input: String,
+}
+
+enum Task {
+    Parse(usize),
+    Part(usize, usize, Arc<ParsedInput>),
+}
+
+struct Queue {
+    tasks: VecDeque<Task>,
+    /// Tasks that are queued or running. Running tasks can queue more, so the
+    /// workers only stop when this gets to zero.
+    pending: usize,
+}
+
+/// Run the selected parts of several days on `threads` threads. Each day is
+/// parsed once, and then its parts can run at the same time. The results are
+/// in the same order as the jobs, whatever order they finish in. A panic in a
+/// solution is caught and reported as an error for the parts it stopped.
+pub fn run_days(
+    jobs: &[Job],
+    parts: &[Part],
+    threads: usize,
+    settings: &Settings,
+) -> Vec<DayResults> {
+    let queue = Mutex::new(Queue {
+        tasks: (0..jobs.len()).map(Task::Parse).collect(),
+        pending: jobs.len(),
+    });
+    let ready = Condvar::new();
+
+    let parsed: Vec<Mutex<Option<Result<(), ParseError>>>> =
+        jobs.iter().map(|_| Mutex::new(None)).collect();
+    let answers: Vec<Vec<Mutex<Option<PartOutcome>>>> = jobs
+        .iter()
+        .map(|_| parts.iter().map(|_| Mutex::new(None)).collect())
+        .collect();
+
+    let worker = || loop {
+        let task = {
+            let mut queue = queue.lock().unwrap();
+
+            loop {
+                if let Some(task) = queue.tasks.pop_front() {
+                    break task;
+                }
+
+                if queue.pending == 0 {
+                    return;
+                }
+
+                queue = ready.wait(queue).unwrap();
+            }
+        };
+
+        let mut new_tasks = Vec::new();
+
+        match task {
+            Task::Parse(i) => {
+                let result = match catch_panic(|| jobs[i].solution.parse(&jobs[i].input)) {
+                    Ok(Ok(input)) => {
+                        let input = Arc::new(input);
+                        new_tasks.extend((0..parts.len()).map(|p| Task::Part(i, p, input.clone())));
+                        Ok(())
+                    }
+                    Ok(Err(e)) => Err(e),
+                    Err(message) => {
+                        for (p, &part) in parts.iter().enumerate() {
+                            *answers[i][p].lock().unwrap() = Some(Err(PartError {
+                                day: jobs[i].day,
+                                part,
+                                message: format!("panicked while parsing: {}", message),
+                            }));
+                        }
+
+                        Ok(())
+                    }
+                };
+
+                *parsed[i].lock().unwrap() = Some(result);
+            }
+            Task::Part(i, p, input) => {
+                let start = Instant::now();
+                let output = run_part(jobs[i].day, jobs[i].solution, &input, parts[p], settings);
+                let time = start.elapsed();
+
+                *answers[i][p].lock().unwrap() = Some(output.map(|output| PartResult {
+                    day: jobs[i].day,
+                    part: parts[p],
+                    answer: output.answer,
+                    stats: output.stats,
+                    time,
+                }));
+            }
+        }
+
+        let mut queue = queue.lock().unwrap();
+        queue.pending += new_tasks.len();
+        queue.pending -= 1;
+        queue.tasks.extend(new_tasks);
+        ready.notify_all();
+    };
+
+    thread::scope(|s| {
+        for _ in 0..threads.max(1) {
+            s.spawn(worker);
+        }
+    });
+
+    parsed
+        .into_iter()
+        .zip(answers)
+        .map(|(parsed, answers)| {
+            parsed.into_inner().unwrap().unwrap()?;
+
+            Ok(answers
+                .into_iter()
+                .map(|a| a.into_inner().unwrap().unwrap())
+                .collect())
+        })
+        .collect()
+}
+
+/// The number of threads to use by default.
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Print a table of results, with the sum of their times and the time that
+/// the whole run took. If answers are given, each result is checked against
+/// them and the number of results that were not ok is returned.
+pub fn print_table(results: &[PartResult], answers: Option<&Answers>, elapsed: Duration) -> usize {
+    let answer_strs: Vec<String> = results.iter().map(|r| r.answer.to_string()).collect();
+    let width = answer_strs
+        .iter()
+        .map(|a| a.len())
+        .max()
+        .unwrap_or(0)
+        .max(6);
+
+    let notes: Vec<String> = results.iter().map(|r| format_stats(&r.stats)).collect();
+    let notes_width = notes.iter().map(|n| n.len()).max().unwrap_or(0);
+
+    print!(
+        "Day  Part  {:<width$}  {:>10}",
+        "Answer",
+        "Time",
+        width = width
+    );
+    if notes_width > 0 {
+        print!("  {:<width$}", "Notes", width = notes_width.max(5));
+    }
+    if answers.is_some() {
+        print!("  Status");
+    }
+    println!();
+
+    let mut not_ok = 0;
+
+    for ((r, answer), note) in results.iter().zip(&answer_strs).zip(&notes) {
+        print!(
+            "{:>3}  {:>4}  {:<width$}  {:>10}",
+            r.day,
+            r.part,
+            answer,
+            format_duration(r.time),
+            width = width
+        );
+
+        if notes_width > 0 {
+            print!("  {:<width$}", note, width = notes_width.max(5));
+        }
+
+        if let Some(answers) = answers {
+            let status = answers.check(r);
+
+            if !status.is_ok() {
+                not_ok += 1;
+            }
+
+            print!("  {}", status);
+        }
+
+        println!();
+    }
+
+    // With several jobs, parts run at the same time, so their sum is more
+    // than the time the run took
+    let total: Duration = results.iter().map(|r| r.time).sum();
+    println!(
+        "{:>width$}  {:>10}",
+        "Sum of parts",
+        format_duration(total),
+        width = width + 11
+    );
+    println!(
+        "{:>width$}  {:>10}",
+        "Elapsed",
+        format_duration(elapsed),
+        width = width + 11
+    );
+
+    not_ok
+}
+
+/// Stats such as `rounds 5`, separated by commas.
+fn format_stats(stats: &[(&str, u64)]) -> String {
+    let stats: Vec<String> = stats
+        .iter()
+        .map(|(name, n)| format!("{} {}", name, n))
+        .collect();
+    stats.join(", ")
+}
+
+/// Print one JSON object per line for each result, and for each part that
+/// could not be run, in order of day and part. If answers are given, each
+/// result is checked against them and the number of results that were not ok
+/// is returned.
+///
+/// ```json
+/// {"day":1,"part":1,"answer":1010884,"type":"unsigned","time_ns":8130}
+/// {"day":11,"part":1,"answer":2368,"type":"unsigned","time_ns":19340210,"rounds":81}
+/// {"day":21,"part":2,"answer":"vmhqr,qxfzc","type":"text","time_ns":410512}
+/// {"day":25,"part":2,"answer":null,"type":"unsolved","time_ns":120}
+/// {"day":7,"part":1,"error":"day 7, line 3, column 1: expected ..."}
+/// ```
+pub fn print_json(
+    results: &[PartResult],
+    errors: &[(u32, Part, String)],
+    answers: Option<&Answers>,
+) -> usize {
+    let (lines, not_ok) = json_lines(results, errors, answers);
+
+    for line in lines {
+        println!("{}", line);
+    }
+
+    not_ok
+}
+
+fn json_lines(
+    results: &[PartResult],
+    errors: &[(u32, Part, String)],
+    answers: Option<&Answers>,
+) -> (Vec<Value>, usize) {
+    let mut lines = Vec::new();
+    let mut not_ok = 0;
+
+    for r in results {
+        let (answer, kind) = match &r.answer {
+            Answer::Signed(n) => (json!(n), "signed"),
+            Answer::Unsigned(n) => (json!(n), "unsigned"),
+            Answer::Text(s) => (json!(s), "text"),
+            Answer::Unsolved => (Value::Null, "unsolved"),
+        };
+
+        let mut value = json!({
+            "day": r.day,
+            "part": part_number(r.part),
+            "answer": answer,
+            "type": kind,
+            "time_ns": r.time.as_nanos() as u64,
+        });
+
+        for &(name, n) in &r.stats {
+            value[name] = json!(n);
+        }
+
+        if let Some(answers) = answers {
+            let status = answers.check(r);
+
+            if !status.is_ok() {
+                not_ok += 1;
+            }
+
+            let name = match &status {
+                Status::Pass => "pass",
+                Status::Fail => "fail",
+                Status::Regressed(expected) => {
+                    value["expected"] = json!(expected);
+                    "regressed"
+                }
+                Status::Unchecked => "unchecked",
+            };
+
+            value["status"] = json!(name);
+        }
+
+        lines.push(((r.day, r.part), value));
+    }
+
+    for (day, part, error) in errors {
+        let value = json!({
+            "day": day,
+            "part": part_number(*part),
+            "error": error,
+        });
+
+        lines.push(((*day, *part), value));
+    }
+
+    lines.sort_by_key(|&(key, _)| key);
+
+    (lines.into_iter().map(|(_, value)| value).collect(), not_ok)
+}
+
+fn part_number(part: Part) -> u32 {
+    match part {
+        Part::One => 1,
+        Part::Two => 2,
+    }
+}
+
+pub fn format_duration(d: Duration) -> String {
+    let micros = d.as_secs_f64() * 1e6;
+
+    if micros < 1e3 {
+        format!("{:.1} µs", micros)
+    } else if micros < 1e6 {
+        format!("{:.1} ms", micros / 1e3)
+    } else {
+        format!("{:.2} s", micros / 1e6)
+    }
+}
+
+#[test]
+fn parse_days_examples() {
+    assert_eq!(parse_days("5").unwrap(), vec![5]);
+    assert_eq!(parse_days("1-4").unwrap(), vec![1, 2, 3, 4]);
+    assert_eq!(parse_days("18,3,7").unwrap(), vec![3, 7, 18]);
+    assert_eq!(parse_days("3,1-4,20-21").unwrap(), vec![1, 2, 3, 4, 20, 21]);
+    assert_eq!(parse_days("all").unwrap(), (1..=25).collect::<Vec<_>>());
+
+    assert!(parse_days("4-1").is_err());
+    assert!(parse_days("0").is_err());
+    assert!(parse_days("26").is_err());
+    assert!(parse_days("1,x").is_err());
+}
+
+#[test]
+fn json_output() {
+    let answers = Answers::parse("[day1]\npart1 = 5\npart2 = 6\n").unwrap();
+    let result = |day, part, answer| PartResult {
+        day,
+        part,
+        answer,
+        stats: Vec::new(),
+        time: Duration::from_micros(2),
+    };
+
+    let results = [
+        result(1, Part::One, 5u32.into()),
+        result(1, Part::Two, (-7i64).into()),
+        PartResult {
+            stats: vec![("rounds", 6)],
+            ..result(11, Part::One, 37u32.into())
+        },
+        result(21, Part::Two, "a,b".into()),
+    ];
+    let errors = [(7, Part::Two, "bad input".to_string())];
+
+    let (lines, not_ok) = json_lines(&results, &errors, Some(&answers));
+    let lines: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+
+    assert_eq!(not_ok, 1);
+    assert_eq!(
+        lines,
+        [
+            r#"{"day":1,"part":1,"answer":5,"type":"unsigned","time_ns":2000,"status":"pass"}"#,
+            r#"{"day":1,"part":2,"answer":-7,"type":"signed","time_ns":2000,"expected":"6","status":"regressed"}"#,
+            r#"{"day":7,"part":2,"error":"bad input"}"#,
+            r#"{"day":11,"part":1,"answer":37,"type":"unsigned","time_ns":2000,"rounds":6,"status":"unchecked"}"#,
+            r#"{"day":21,"part":2,"answer":"a,b","type":"text","time_ns":2000,"status":"unchecked"}"#,
+        ]
+    );
+}
+
+#[test]
+fn run_days_in_order() {
+    let job = |day, input: &str| Job {
+        day,
+        solution: registry::get(day).unwrap(),
+        input: input.to_string(),
+    };
+
+    let jobs = [
+        job(12, "F10\nN3\nF7\nR90\nF11\n"),
+        job(1, "1721\n979\n366\n299\n675\n1456\n"),
+        job(3, "..#\n#x.\n"),
+        job(
+            8,
+            "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6\n",
+        ),
+    ];
+
+    let results = run_days(&jobs, &Part::BOTH, 3, &Settings::default());
+    let answers = |i: usize| -> Vec<(u32, Part, String)> {
+        results[i]
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|r| r.as_ref().unwrap())
+            .map(|r| (r.day, r.part, r.answer.to_string()))
+            .collect()
+    };
+
+    assert_eq!(results.len(), 4);
+    assert_eq!(
+        answers(0),
+        [(12, Part::One, "25".into()), (12, Part::Two, "286".into())]
+    );
+    assert_eq!(
+        answers(1),
+        [
+            (1, Part::One, "514579".into()),
+            (1, Part::Two, "241861950".into())
+        ]
+    );
+    assert_eq!(results[2].as_ref().unwrap_err().line, 2);
+    assert_eq!(answers(3)[1], (8, Part::Two, "8".into()));
+}
+
+#[test]
+fn run_days_catches_panics() {
+    use crate::solution::Solution;
+
+    struct Panics;
+
+    impl Solution for Panics {
+        const DAY: u32 = 99;
+
+        type Input = u32;
+
+        fn parse(&self, input: &str) -> Result<u32, ParseError> {
+            match input {
+                "parse" => panic!("bad input"),
+                _ => Ok(0),
+            }
+        }
+
+        fn part1(&self, _input: &u32) -> Answer {
+            panic!("no answer")
+        }
+
+        fn part2(&self, _input: &u32) -> Answer {
+            7u32.into()
+        }
+    }
+
+    let job = |input: &str| Job {
+        day: 99,
+        solution: &Panics,
+        input: input.to_string(),
+    };
+
+    let results = run_days(
+        &[job(""), job("parse"), job("")],
+        &Part::BOTH,
+        4,
+        &Settings::default(),
+    );
+    let parts: Vec<Vec<Result<String, String>>> = results
+        .into_iter()
+        .map(|r| {
+            r.unwrap()
+                .into_iter()
+                .map(|r| r.map(|r| r.answer.to_string()).map_err(|e| e.to_string()))
+                .collect()
+        })
+        .collect();
+
+    let part1 = Err("day 99, part 1: panicked: no answer".to_string());
+    assert_eq!(parts[0], [part1.clone(), Ok("7".to_string())]);
+    assert_eq!(parts[2], [part1, Ok("7".to_string())]);
+    assert_eq!(
+        parts[1][1],
+        Err("day 99, part 2: panicked while parsing: bad input".to_string())
+    );
+}
+
+#[test]
+fn run_days_with_max_rounds() {
+    let layout = "L.LL.LL.LL\nLLLLLLL.LL\nL.L.L..L..\nLLLL.LL.LL\nL.LL.LL.LL\n\
+                  L.LLLLL.LL\n..L.L.....\nLLLLLLLLLL\nL.LLLLLL.L\nL.LLLLL.LL\n";
+    let run = |max_rounds| {
+        let settings = Settings {
+            max_rounds: Some(max_rounds),
+        };
+        run_day(
+            11,
+            registry::get(11).unwrap(),
+            layout,
+            &Part::BOTH,
+            &settings,
+        )
+        .unwrap()
+    };
+
+    // The limit is the number of rounds in which someone moves
+    let results = run(5);
+    assert_eq!(results[0].as_ref().unwrap().stats, [("rounds", 5)]);
+    assert_eq!(
+        results[1].as_ref().unwrap_err().message,
+        "The seating has not settled after 5 rounds"
+    );
+
+    let results = run(6);
+    assert_eq!(results[1].as_ref().unwrap().answer, 26u32.into());
+    assert_eq!(results[1].as_ref().unwrap().stats, [("rounds", 6)]);
+}
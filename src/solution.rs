@@ -0,0 +1,158 @@
+use crate::parse::ParseError;
+use anyhow::Error;
+use std::any::Any;
+use std::fmt;
+
+/// The answer to one part of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Signed(i64),
+    Unsigned(u64),
+    Text(String),
+    Unsolved,
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Signed(n) => n.fmt(f),
+            Answer::Unsigned(n) => n.fmt(f),
+            Answer::Text(s) => s.fmt(f),
+            Answer::Unsolved => f.pad("-"),
+        }
+    }
+}
+
+macro_rules! impl_from_int {
+    ($variant:ident, $target:ty, $($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Answer {
+                    Answer::$variant(n as $target)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(Signed, i64, i32, i64);
+impl_from_int!(Unsigned, u64, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Answer {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Answer {
+        Answer::Text(s.to_string())
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Part::One => f.pad("1"),
+            Part::Two => f.pad("2"),
+        }
+    }
+}
+
+/// Settings from the command line, for the solutions that have any.
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    /// How many rounds a simulation can take before giving up.
+    pub max_rounds: Option<usize>,
+}
+
+/// What a part found: its answer, and numbers about how it got there, such as
+/// how many rounds a simulation took.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Output {
+    pub answer: Answer,
+    pub stats: Vec<(&'static str, u64)>,
+}
+
+impl From<Answer> for Output {
+    fn from(answer: Answer) -> Output {
+        Output {
+            answer,
+            stats: Vec::new(),
+        }
+    }
+}
+
+/// A solution to one day's puzzle.
+///
+/// The input is parsed once and then shared by both parts.
+pub trait Solution {
+    const DAY: u32;
+
+    type Input;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, ParseError>;
+
+    fn part1(&self, _input: &Self::Input) -> Answer {
+        Answer::Unsolved
+    }
+
+    fn part2(&self, _input: &Self::Input) -> Answer {
+        Answer::Unsolved
+    }
+
+    /// Run one part. Solutions that use the settings, report stats or can
+    /// fail override this instead of `part1` and `part2`.
+    fn run(&self, input: &Self::Input, part: Part, _settings: &Settings) -> Result<Output, Error> {
+        Ok(match part {
+            Part::One => self.part1(input),
+            Part::Two => self.part2(input),
+        }
+        .into())
+    }
+}
+
+pub type ParsedInput = Box<dyn Any + Send + Sync>;
+
+/// Object-safe version of `Solution`, so that days with different input types
+/// can be stored together in the registry.
+pub trait DynSolution: Sync {
+    fn day(&self) -> u32;
+    fn parse(&self, input: &str) -> Result<ParsedInput, ParseError>;
+    fn part(&self, input: &ParsedInput, part: Part, settings: &Settings) -> Result<Output, Error>;
+}
+
+impl<S> DynSolution for S
+where
+    S: Solution + Sync,
+    S::Input: Send + Sync + 'static,
+{
+    fn day(&self) -> u32 {
+        S::DAY
+    }
+
+    fn parse(&self, input: &str) -> Result<ParsedInput, ParseError> {
+        match Solution::parse(self, input) {
+            Ok(input) => Ok(Box::new(input)),
+            Err(e) => Err(e.in_day(S::DAY)),
+        }
+    }
+
+    fn part(&self, input: &ParsedInput, part: Part, settings: &Settings) -> Result<Output, Error> {
+        let input = input
+            .downcast_ref::<S::Input>()
+            .expect("Input was parsed by a different solution");
+
+        self.run(input, part, settings)
+    }
+}
@@ -0,0 +1,374 @@
+use crate::fetch::Client;
+use crate::solution::Part;
+use anyhow::{anyhow, bail, Context, Error};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_LOG_PATH: &str = "submissions.log";
+
+/// What the website made of a submitted answer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Right,
+    Wrong,
+    /// The answer was not checked because the last one was too recent.
+    TooSoon,
+    /// The part was already solved, or the previous part has not been.
+    WrongLevel,
+    Unknown,
+}
+
+impl Verdict {
+    fn name(self) -> &'static str {
+        match self {
+            Verdict::Right => "right",
+            Verdict::Wrong => "wrong",
+            Verdict::TooSoon => "too-soon",
+            Verdict::WrongLevel => "wrong-level",
+            Verdict::Unknown => "unknown",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Verdict> {
+        [
+            Verdict::Right,
+            Verdict::Wrong,
+            Verdict::TooSoon,
+            Verdict::WrongLevel,
+            Verdict::Unknown,
+        ]
+        .iter()
+        .copied()
+        .find(|v| v.name() == name)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+/// The website's response to a submission.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub verdict: Verdict,
+    /// How many seconds to wait before submitting again, if we were told.
+    pub wait: Option<u64>,
+    pub message: String,
+}
+
+impl Response {
+    pub fn parse(html: &str) -> Response {
+        lazy_static! {
+            static ref ARTICLE_RE: Regex =
+                Regex::new(r"(?s)<article[^>]*>(.*?)</article>").unwrap();
+            static ref TAG_RE: Regex = Regex::new(r"<[^>]*>").unwrap();
+            static ref LEFT_RE: Regex =
+                Regex::new(r"You have (?:(\d+)m )?(\d+)s left to wait").unwrap();
+            static ref WAIT_RE: Regex =
+                Regex::new(r"wait (one|\d+) minutes? before trying again").unwrap();
+        }
+
+        let article = match ARTICLE_RE.captures(html) {
+            Some(caps) => caps.get(1).unwrap().as_str(),
+            None => html,
+        };
+        let message = TAG_RE
+            .replace_all(article, "")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let verdict = if message.contains("That's the right answer") {
+            Verdict::Right
+        } else if message.contains("That's not the right answer") {
+            Verdict::Wrong
+        } else if message.contains("You gave an answer too recently") {
+            Verdict::TooSoon
+        } else if message.contains("You don't seem to be solving the right level") {
+            Verdict::WrongLevel
+        } else {
+            Verdict::Unknown
+        };
+
+        let wait = if let Some(caps) = LEFT_RE.captures(&message) {
+            let minutes: u64 = caps.get(1).map_or(0, |m| m.as_str().parse().unwrap());
+            let seconds: u64 = caps[2].parse().unwrap();
+            Some(minutes * 60 + seconds)
+        } else if let Some(caps) = WAIT_RE.captures(&message) {
+            let minutes: u64 = match &caps[1] {
+                "one" => 1,
+                n => n.parse().unwrap(),
+            };
+            Some(minutes * 60)
+        } else {
+            None
+        };
+
+        Response {
+            verdict,
+            wait,
+            message,
+        }
+    }
+}
+
+/// One line of the submission log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    /// Seconds since the Unix epoch.
+    pub time: u64,
+    pub day: u32,
+    pub part: Part,
+    pub answer: String,
+    pub verdict: Verdict,
+    pub wait: Option<u64>,
+}
+
+/// Every answer that has been submitted, so that we never send the same wrong
+/// answer twice and do not submit while the website wants us to wait.
+///
+/// Each line of the log is `time day part verdict wait answer`, separated by
+/// tabs, with `-` for an unknown wait.
+#[derive(Debug)]
+pub struct SubmissionLog {
+    path: PathBuf,
+    submissions: Vec<Submission>,
+}
+
+impl SubmissionLog {
+    pub fn load(path: &Path) -> Result<SubmissionLog, Error> {
+        let s = match fs::read_to_string(path) {
+            Ok(s) => s,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e).with_context(|| format!("Could not read {}", path.display())),
+        };
+
+        let submissions = s
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+            .map(|(i, l)| {
+                parse_submission(l).ok_or_else(|| {
+                    anyhow!(
+                        "Could not parse {}: line {} is invalid",
+                        path.display(),
+                        i + 1
+                    )
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(SubmissionLog {
+            path: path.to_path_buf(),
+            submissions,
+        })
+    }
+
+    pub fn submissions(&self) -> &[Submission] {
+        &self.submissions
+    }
+
+    /// Check that submitting an answer now would not be a waste of time.
+    pub fn check(&self, day: u32, part: Part, answer: &str, now: u64) -> Result<(), Error> {
+        for s in &self.submissions {
+            if s.day != day || s.part != part {
+                continue;
+            }
+
+            if s.verdict == Verdict::Right {
+                bail!(
+                    "Day {} part {} was already solved with {}",
+                    day,
+                    part,
+                    s.answer
+                );
+            }
+
+            if s.verdict == Verdict::Wrong && s.answer == answer {
+                bail!(
+                    "{} was already submitted for day {} part {} and was wrong",
+                    answer,
+                    day,
+                    part
+                );
+            }
+        }
+
+        if let Some(until) = self.wait_until() {
+            if now < until {
+                bail!(
+                    "The website asked us to wait, try again in {}s",
+                    until - now
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The time before which the website does not want another submission.
+    pub fn wait_until(&self) -> Option<u64> {
+        self.submissions
+            .iter()
+            .filter_map(|s| s.wait.map(|wait| s.time + wait))
+            .max()
+    }
+
+    pub fn record(&mut self, submission: Submission) -> Result<(), Error> {
+        let wait = match submission.wait {
+            Some(wait) => wait.to_string(),
+            None => "-".to_string(),
+        };
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Could not open {}", self.path.display()))?;
+
+        writeln!(
+            file,
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            submission.time,
+            submission.day,
+            submission.part,
+            submission.verdict,
+            wait,
+            submission.answer
+        )
+        .with_context(|| format!("Could not write {}", self.path.display()))?;
+
+        self.submissions.push(submission);
+
+        Ok(())
+    }
+}
+
+fn parse_submission(line: &str) -> Option<Submission> {
+    let mut fields = line.splitn(6, '\t');
+
+    let time = fields.next()?.parse().ok()?;
+    let day = fields.next()?.parse().ok()?;
+    let part = match fields.next()? {
+        "1" => Part::One,
+        "2" => Part::Two,
+        _ => return None,
+    };
+    let verdict = Verdict::from_name(fields.next()?)?;
+    let wait = match fields.next()? {
+        "-" => None,
+        wait => Some(wait.parse().ok()?),
+    };
+    let answer = fields.next()?.to_string();
+
+    Some(Submission {
+        time,
+        day,
+        part,
+        answer,
+        verdict,
+        wait,
+    })
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Submit an answer, unless the log says that there is no point, and record
+/// the response in the log.
+pub fn submit(
+    client: &Client,
+    log: &mut SubmissionLog,
+    day: u32,
+    part: Part,
+    answer: &str,
+) -> Result<Response, Error> {
+    let time = now();
+    log.check(day, part, answer, time)?;
+
+    let level = part.to_string();
+    let html = client.post_form(
+        &format!("/day/{}/answer", day),
+        &[("level", &level), ("answer", answer)],
+    )?;
+    let response = Response::parse(&html);
+
+    log.record(Submission {
+        time,
+        day,
+        part,
+        answer: answer.to_string(),
+        verdict: response.verdict,
+        wait: response.wait,
+    })?;
+
+    Ok(response)
+}
+
+#[test]
+fn parse_responses() {
+    let right = Response::parse(
+        "<main>\n<article><p>That's the right answer!  You are <em>one gold star</em> closer.</p></article>\n</main>",
+    );
+    assert_eq!(right.verdict, Verdict::Right);
+    assert_eq!(right.wait, None);
+    assert_eq!(
+        right.message,
+        "That's the right answer! You are one gold star closer."
+    );
+
+    let wrong = Response::parse("<article><p>That's not the right answer; your answer is too high.  Please wait one minute before trying again.</p></article>");
+    assert_eq!(wrong.verdict, Verdict::Wrong);
+    assert_eq!(wrong.wait, Some(60));
+
+    let too_soon = Response::parse("<article><p>You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 9s left to wait.</p></article>");
+    assert_eq!(too_soon.verdict, Verdict::TooSoon);
+    assert_eq!(too_soon.wait, Some(69));
+
+    let level = Response::parse("<article><p>You don't seem to be solving the right level.  Did you already complete it?</p></article>");
+    assert_eq!(level.verdict, Verdict::WrongLevel);
+}
+
+#[test]
+fn submit_to_stand_in_server() {
+    let dir = std::env::temp_dir().join(format!("aoc-submit-test-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("submissions.log");
+
+    let mut log = SubmissionLog::load(&path).unwrap();
+    let (base_url, server) = crate::fetch::serve_once(
+        200,
+        "<article><p>That's not the right answer.  Please wait 5 minutes before trying again.</p></article>",
+    );
+    let client = Client::new(&base_url, "abc");
+
+    let response = submit(&client, &mut log, 1, Part::Two, "1234").unwrap();
+    assert_eq!(response.verdict, Verdict::Wrong);
+
+    let request = server.join().unwrap();
+    assert!(request.starts_with("POST /day/1/answer "));
+    assert!(request.ends_with("level=2&answer=1234"));
+
+    // The log survives a reload, and stops us resubmitting or submitting
+    // before the wait is over
+    let log = SubmissionLog::load(&path).unwrap();
+    let s = &log.submissions()[0];
+    assert_eq!((s.day, s.part, s.answer.as_str()), (1, Part::Two, "1234"));
+    assert!(log.check(1, Part::Two, "1234", s.time + 600).is_err());
+    assert!(log.check(1, Part::Two, "1235", s.time + 10).is_err());
+    assert!(log.check(1, Part::Two, "1235", s.time + 300).is_ok());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
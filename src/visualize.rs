@@ -0,0 +1,275 @@
+use crate::automaton::{Automaton, Topology};
+use crate::solution::{Part, Settings, Solution};
+use crate::{day11, day17, day24};
+use anyhow::{anyhow, bail, Context, Error};
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, IsTerminal, Write};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+pub const DEFAULT_DELAY: Duration = Duration::from_millis(100);
+
+/// The days that are cellular automata, and so can be visualized.
+pub const DAYS: [u32; 3] = [11, 17, 24];
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// How long to show each frame for.
+    pub delay: Duration,
+    /// Wait for Enter after each frame instead.
+    pub step: bool,
+    /// Also write every frame to this file.
+    pub frames: Option<PathBuf>,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            delay: DEFAULT_DELAY,
+            step: false,
+            frames: None,
+        }
+    }
+}
+
+/// Where frames are shown: stdout or another writer, and perhaps a file.
+pub struct Screen {
+    options: Options,
+    output: Box<dyn Write>,
+    /// Whether the output is a terminal, which is cleared between frames.
+    terminal: bool,
+    file: Option<BufWriter<File>>,
+}
+
+impl Screen {
+    pub fn new(options: Options) -> Result<Screen, Error> {
+        let mut screen = Screen::with_output(options, Box::new(io::stdout()))?;
+        screen.terminal = io::stdout().is_terminal();
+        Ok(screen)
+    }
+
+    /// Show frames by writing them to `output` instead of stdout.
+    pub fn with_output(options: Options, output: Box<dyn Write>) -> Result<Screen, Error> {
+        let file = match &options.frames {
+            Some(path) => {
+                Some(BufWriter::new(File::create(path).with_context(|| {
+                    format!("Could not create {}", path.display())
+                })?))
+            }
+            None => None,
+        };
+
+        Ok(Screen {
+            options,
+            output,
+            terminal: false,
+            file,
+        })
+    }
+
+    /// Show a frame, and then wait. Returns false if the user asked to stop.
+    pub fn show(&mut self, title: &str, frame: &str) -> Result<bool, Error> {
+        if let Some(file) = &mut self.file {
+            writeln!(file, "{}\n{}", title, frame).context("Could not write a frame")?;
+        }
+
+        let out = &mut self.output;
+
+        if self.terminal {
+            // Clear the screen and move to the top left
+            write!(out, "\x1b[2J\x1b[H")?;
+        }
+
+        writeln!(out, "{}\n{}", title, frame)?;
+
+        if self.options.step {
+            write!(
+                out,
+                "Press Enter for the next frame, or q and Enter to stop: "
+            )?;
+            out.flush()?;
+
+            let mut line = String::new();
+            if io::stdin().lock().read_line(&mut line)? == 0 || line.trim() == "q" {
+                return Ok(false);
+            }
+        } else {
+            out.flush()?;
+
+            if !self.options.delay.is_zero() {
+                thread::sleep(self.options.delay);
+            }
+        }
+
+        Ok(true)
+    }
+
+    pub fn finish(&mut self) -> Result<(), Error> {
+        if let Some(file) = &mut self.file {
+            file.flush().context("Could not write a frame")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Show every generation of an automaton up to `generations`, or until it
+/// stops changing. If it stops changing, the last frame is shown again with
+/// the number of generations it took.
+pub fn animate<T: Topology>(
+    screen: &mut Screen,
+    title: &str,
+    automaton: &mut Automaton<T>,
+    generations: usize,
+    render: impl Fn(&Automaton<T>) -> String,
+) -> Result<(), Error> {
+    loop {
+        let frame_title = format!(
+            "{}, generation {}, {} alive",
+            title,
+            automaton.generation(),
+            automaton.population()
+        );
+
+        if !screen.show(&frame_title, &render(automaton))? {
+            break;
+        }
+
+        if automaton.generation() >= generations {
+            break;
+        }
+
+        if !automaton.step() {
+            let settled_title = format!(
+                "{}, settled after {} generations, {} alive",
+                title,
+                automaton.generation() - 1,
+                automaton.population()
+            );
+
+            screen.show(&settled_title, &render(automaton))?;
+            break;
+        }
+    }
+
+    screen.finish()
+}
+
+/// Animate one part of a day that is a cellular automaton.
+pub fn visualize(
+    screen: &mut Screen,
+    day: u32,
+    part: Part,
+    input: &str,
+    settings: &Settings,
+) -> Result<(), Error> {
+    let title = format!("Day {} part {}", day, part);
+    let parse_error = |e: crate::parse::ParseError| anyhow!("{}", e.in_day(day).diagnostic());
+
+    match day {
+        11 => {
+            let layout = day11::parse_layout(input).map_err(parse_error)?;
+            let mut seats = match part {
+                Part::One => day11::seating_part1(&layout),
+                Part::Two => day11::seating_part2(&layout),
+            };
+
+            let max_rounds = settings.max_rounds.unwrap_or(day11::MAX_ROUNDS);
+
+            animate(screen, &title, &mut seats, max_rounds, |s| {
+                day11::render(&layout, s)
+            })
+        }
+        17 => {
+            let active = day17::Day17.parse(input).map_err(parse_error)?;
+
+            match part {
+                Part::One => animate(
+                    screen,
+                    &title,
+                    &mut day17::cubes::<3>(&active),
+                    day17::CYCLES,
+                    |c| day17::render(c.live()),
+                ),
+                Part::Two => animate(
+                    screen,
+                    &title,
+                    &mut day17::cubes::<4>(&active),
+                    day17::CYCLES,
+                    |c| day17::render(c.live()),
+                ),
+            }
+        }
+        24 => {
+            let black = day24::Day24.parse(input).map_err(parse_error)?;
+
+            match part {
+                Part::One => screen
+                    .show(&title, &day24::render(&black.into_iter().collect()))
+                    .and_then(|_| screen.finish()),
+                Part::Two => animate(
+                    screen,
+                    &title,
+                    &mut day24::floor(&black),
+                    day24::DAYS,
+                    |f| day24::render(f.live()),
+                ),
+            }
+        }
+        _ => bail!(
+            "Day {} cannot be visualized, only days 11, 17 and 24 can",
+            day
+        ),
+    }
+}
+
+#[test]
+fn frames_to_file() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(bytes)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let path = std::env::temp_dir().join(format!("aoc-frames-test-{}.txt", std::process::id()));
+    let output = Buffer::default();
+    let mut screen = Screen::with_output(
+        Options {
+            delay: Duration::ZERO,
+            step: false,
+            frames: Some(path.clone()),
+        },
+        Box::new(output.clone()),
+    )
+    .unwrap();
+
+    let settings = Settings::default();
+    visualize(&mut screen, 11, Part::One, "L.L\nLLL\n", &settings).unwrap();
+    visualize(&mut screen, 24, Part::One, "e\nne\nnw\n", &settings).unwrap();
+
+    let frames = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        frames,
+        "Day 11 part 1, generation 0, 0 alive\nL.L\nLLL\n\n\
+         Day 11 part 1, generation 1, 5 alive\n#.#\n###\n\n\
+         Day 11 part 1, generation 2, 4 alive\n#.#\n#L#\n\n\
+         Day 11 part 1, settled after 2 generations, 4 alive\n#.#\n#L#\n\n\
+         Day 24 part 1\n# #\n . #\n\n"
+    );
+    assert_eq!(String::from_utf8(output.0.take()).unwrap(), frames);
+
+    assert!(visualize(&mut screen, 1, Part::One, "", &settings).is_err());
+}